@@ -1,8 +1,13 @@
-use std::{fs::File, io::ErrorKind, path::PathBuf};
-use tag::{Tag, TagReadError};
+use std::{
+    fs::File,
+    io::{ErrorKind, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use tag::{header::HeaderReadError, scanner::find_header, Tag, TagReadError};
 use thiserror::Error;
 
 pub mod errors;
+pub mod mpeg;
 pub mod tag;
 pub mod utils;
 
@@ -18,37 +23,162 @@ pub enum FileReadError {
     TagReadingError(String, TagReadError),
 }
 
-pub fn read_file(filename: &PathBuf) -> Result<Tag, FileReadError> {
-    let mut file = File::open(filename).map_err(|fserror| match fserror.kind() {
+fn file_open_error(filename: &Path, fserror: std::io::Error) -> FileReadError {
+    match fserror.kind() {
         ErrorKind::NotFound => FileReadError::FileNotFound(filename.to_string_lossy().to_string()),
         ErrorKind::PermissionDenied => {
             FileReadError::MissingReadPermissions(filename.to_string_lossy().to_string())
         }
         _ => FileReadError::FileSystemError(filename.to_string_lossy().to_string()),
+    }
+}
+
+/// in bytes, scanned for a tag when a file doesn't start with one, as some have junk or a RIFF
+/// header before it
+const FILE_SCAN_LIMIT: u64 = 8 * 1024;
+
+/// Reads the tag at the start of the file, or else the first one found in its first few KiB.
+pub fn read_file(filename: &PathBuf) -> Result<Tag, FileReadError> {
+    let mut file = File::open(filename).map_err(|fserror| file_open_error(filename, fserror))?;
+
+    let tag = read_tag_near_start(&mut file).map_err(|err| {
+        FileReadError::TagReadingError(filename.to_string_lossy().to_string(), err)
     })?;
 
-    let tag = tag::Tag::read(&mut file).map_err(|err| {
+    Ok(tag)
+}
+
+/// Offset of the tag in the first bytes of a file: 0 when the file starts with one, or else the
+/// first plausible tag header.
+fn tag_offset_near_start(prefix: &[u8]) -> Option<u64> {
+    if prefix.starts_with(b"ID3") {
+        return Some(0);
+    }
+    find_header(prefix).map(|offset| offset as u64)
+}
+
+/// Leaves the reader at the tag found by [`tag_offset_near_start`], and returns its offset.
+fn seek_tag_near_start(reader: &mut (impl Read + Seek)) -> std::io::Result<Option<u64>> {
+    let mut prefix = vec![];
+    reader.rewind()?;
+    reader.take(FILE_SCAN_LIMIT).read_to_end(&mut prefix)?;
+    let offset = tag_offset_near_start(&prefix);
+    reader.seek(SeekFrom::Start(offset.unwrap_or(0)))?;
+    Ok(offset)
+}
+
+fn read_tag_near_start(reader: &mut (impl Read + Seek)) -> Result<Tag, TagReadError> {
+    match seek_tag_near_start(reader) {
+        Ok(Some(_)) => Tag::read(reader),
+        Ok(None) => Err(HeaderReadError::ID3NotFound.into()),
+        Err(error) => Err(TagReadError::ScanError(error.kind())),
+    }
+}
+
+/// Same as [`seek_tag_near_start`], for files of the async runtime.
+#[cfg(feature = "async")]
+async fn seek_tag_near_start_async(file: &mut tokio::fs::File) -> std::io::Result<Option<u64>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut prefix = vec![];
+    file.rewind().await?;
+    (&mut *file)
+        .take(FILE_SCAN_LIMIT)
+        .read_to_end(&mut prefix)
+        .await?;
+    let offset = tag_offset_near_start(&prefix);
+    file.seek(SeekFrom::Start(offset.unwrap_or(0))).await?;
+    Ok(offset)
+}
+
+/// Same as [`read_file`], without blocking the async runtime.
+#[cfg(feature = "async")]
+pub async fn read_file_async(filename: &PathBuf) -> Result<Tag, FileReadError> {
+    let mut file = tokio::fs::File::open(filename)
+        .await
+        .map_err(|fserror| file_open_error(filename, fserror))?;
+
+    let read_tag = match seek_tag_near_start_async(&mut file).await {
+        Ok(Some(_)) => Tag::read_async(&mut tokio::io::BufReader::new(file)).await,
+        Ok(None) => Err(HeaderReadError::ID3NotFound.into()),
+        Err(error) => Err(TagReadError::ScanError(error.kind())),
+    };
+    let tag = read_tag.map_err(|err| {
         FileReadError::TagReadingError(filename.to_string_lossy().to_string(), err)
     })?;
 
     Ok(tag)
 }
 
-pub fn find_substring(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
-    if pattern.len() == 0 {
-        return None;
+/// Writes the tag in place of the tag [`read_file`] would read, or at the start of the file if
+/// there is none. The new file is written next to the old one and renamed over it, so the file
+/// is left as it was if writing fails.
+#[cfg(feature = "async")]
+pub async fn write_file_async(filename: &PathBuf, tag: &Tag) -> std::io::Result<()> {
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(filename.file_name().unwrap_or_default());
+    temp_name.push(".id3v2-tmp");
+    let temp_filename = filename.with_file_name(temp_name);
+
+    let written = write_file_async_to(filename, &temp_filename, tag).await;
+    let renamed = match written {
+        Ok(()) => tokio::fs::rename(&temp_filename, filename).await,
+        Err(err) => Err(err),
+    };
+    if renamed.is_err() {
+        let _ = tokio::fs::remove_file(&temp_filename).await;
     }
+    renamed
+}
 
-    let mut iter = bytes.iter();
-    while let Some(byte_position) = iter.position(|b| *b == pattern[0]) {
-        let max_position = std::cmp::min(pattern.len() + byte_position, bytes.len());
-        let bytes = &(bytes[byte_position..max_position]);
-        dbg!(bytes);
-        if bytes == pattern {
-            return Some(byte_position);
+#[cfg(feature = "async")]
+async fn write_file_async_to(
+    filename: &Path,
+    temp_filename: &Path,
+    tag: &Tag,
+) -> std::io::Result<()> {
+    use tag::header::{Header, HeaderFlagType};
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+    let mut file = tokio::fs::File::open(filename).await?;
+    let old_tag_start = seek_tag_near_start_async(&mut file).await?.unwrap_or(0);
+    let mut header_bytes = vec![];
+    (&mut file).take(10).read_to_end(&mut header_bytes).await?;
+    let old_tag_size = match Header::read(&mut &header_bytes[..]) {
+        Ok(header) => {
+            let footer_size = if header.is_flag_set(&HeaderFlagType::FooterPresent) {
+                header.bytes_size()
+            } else {
+                0
+            };
+            (header.bytes_size() + header.tag_size() + footer_size) as u64
         }
+        Err(_) => 0,
+    };
+
+    let mut temp_file = tokio::fs::File::create(temp_filename).await?;
+    temp_file
+        .set_permissions(file.metadata().await?.permissions())
+        .await?;
+    // The bytes before the old tag are kept, as a RIFF header
+    file.rewind().await?;
+    tokio::io::copy(&mut (&mut file).take(old_tag_start), &mut temp_file).await?;
+    temp_file.write_all(&tag.to_bytes()).await?;
+    file.seek(SeekFrom::Start(old_tag_start + old_tag_size))
+        .await?;
+    tokio::io::copy(&mut file, &mut temp_file).await?;
+    temp_file.sync_all().await
+}
+
+/// Offset of the first occurrence of the pattern in the bytes.
+pub fn find_substring(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
     }
-    None
+
+    bytes
+        .windows(pattern.len())
+        .position(|window| window == pattern)
 }
 
 #[cfg(test)]
@@ -67,4 +197,80 @@ mod tests {
 
         assert_eq!(find_substring(bytes, pattern), Some(4))
     }
+
+    #[test]
+    fn find_substring_edges() {
+        assert_eq!(find_substring(b"aab", b"ab"), Some(1));
+        assert_eq!(find_substring(b"abc", b"bc"), Some(1));
+        assert_eq!(find_substring(b"ab", b"abc"), None);
+        assert_eq!(find_substring(b"abc", b""), None);
+    }
+
+    #[test]
+    fn tag_near_start() {
+        let tag = tag::builder::TagBuilder::new()
+            .frame(tag::test_utils::frame(b"TIT2", b"\x00Title"))
+            .build()
+            .unwrap();
+        let read = |junk_size: usize| {
+            let bytes = [vec![0; junk_size], tag.to_bytes(), make_bytes("audio")].concat();
+            read_tag_near_start(&mut std::io::Cursor::new(bytes))
+        };
+
+        assert_eq!(read(0), Ok(tag.clone()));
+        assert_eq!(read(1000), Ok(tag.clone()));
+        assert_eq!(
+            read(FILE_SCAN_LIMIT as usize),
+            Err(TagReadError::HeaderError(HeaderReadError::ID3NotFound))
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn write_file_in_place_of_old_tag() {
+        let directory = std::env::temp_dir().join("id3v2-write-file-async");
+        std::fs::create_dir_all(&directory).unwrap();
+        let filename = directory.join("song.mp3");
+
+        let old_tag = tag::builder::TagBuilder::new()
+            .footer(true)
+            .frame(tag::test_utils::frame(b"TIT2", b"\x00Old title"))
+            .build()
+            .unwrap();
+        std::fs::write(
+            &filename,
+            [old_tag.to_bytes(), make_bytes("audio")].concat(),
+        )
+        .unwrap();
+
+        let new_tag = tag::builder::TagBuilder::new()
+            .padding(100)
+            .build()
+            .unwrap();
+        write_file_async(&filename, &new_tag).await.unwrap();
+        assert_eq!(
+            std::fs::read(&filename).unwrap(),
+            [new_tag.to_bytes(), make_bytes("audio")].concat()
+        );
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+
+        // The tag after junk is replaced where it is, and read the same way as by read_file
+        let junk = make_bytes("RIFF\x24\x00\x00\x00WAVEid3 ");
+        std::fs::write(
+            &filename,
+            [junk.clone(), old_tag.to_bytes(), make_bytes("audio")].concat(),
+        )
+        .unwrap();
+        assert_eq!(read_file_async(&filename).await, Ok(old_tag.clone()));
+        assert_eq!(read_file(&filename), Ok(old_tag));
+
+        write_file_async(&filename, &new_tag).await.unwrap();
+        assert_eq!(
+            std::fs::read(&filename).unwrap(),
+            [junk, new_tag.to_bytes(), make_bytes("audio")].concat()
+        );
+        assert_eq!(read_file_async(&filename).await, Ok(new_tag));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
 }
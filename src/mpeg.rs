@@ -0,0 +1,203 @@
+use std::io::{ErrorKind, Read};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MpegVersion {
+    Mpeg1,
+    Mpeg2,
+    Mpeg25,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MpegLayer {
+    Layer1,
+    Layer2,
+    Layer3,
+}
+
+const BITRATES_V1_L1: [u32; 15] = [
+    0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+];
+const BITRATES_V1_L2: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+];
+const BITRATES_V1_L3: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const BITRATES_V2_L1: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+];
+const BITRATES_V2_L2_L3: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// The 4 byte header at the start of every MPEG audio frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MpegFrameHeader {
+    pub version: MpegVersion,
+    pub layer: MpegLayer,
+    /// in kbit/s
+    pub bitrate: u32,
+    /// in Hz
+    pub sample_rate: u32,
+    pub padding: bool,
+}
+
+impl MpegFrameHeader {
+    /// Parses a frame header, returning `None` if the bytes aren't a valid header. Free format
+    /// bitrates are not supported, since their frame length cannot be known from the header alone.
+    pub fn parse(bytes: [u8; 4]) -> Option<Self> {
+        if bytes[0] != 0xFF || bytes[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+
+        let version = match (bytes[1] >> 3) & 0b11 {
+            0b00 => MpegVersion::Mpeg25,
+            0b10 => MpegVersion::Mpeg2,
+            0b11 => MpegVersion::Mpeg1,
+            _ => return None,
+        };
+        let layer = match (bytes[1] >> 1) & 0b11 {
+            0b01 => MpegLayer::Layer3,
+            0b10 => MpegLayer::Layer2,
+            0b11 => MpegLayer::Layer1,
+            _ => return None,
+        };
+
+        let bitrate_index = (bytes[2] >> 4) as usize;
+        if bitrate_index == 0 || bitrate_index == 0b1111 {
+            return None;
+        }
+        let bitrates = match (version, layer) {
+            (MpegVersion::Mpeg1, MpegLayer::Layer1) => &BITRATES_V1_L1,
+            (MpegVersion::Mpeg1, MpegLayer::Layer2) => &BITRATES_V1_L2,
+            (MpegVersion::Mpeg1, MpegLayer::Layer3) => &BITRATES_V1_L3,
+            (_, MpegLayer::Layer1) => &BITRATES_V2_L1,
+            (_, _) => &BITRATES_V2_L2_L3,
+        };
+
+        let base_sample_rate = match (bytes[2] >> 2) & 0b11 {
+            0b00 => 44100,
+            0b01 => 48000,
+            0b10 => 32000,
+            _ => return None,
+        };
+        let sample_rate = match version {
+            MpegVersion::Mpeg1 => base_sample_rate,
+            MpegVersion::Mpeg2 => base_sample_rate / 2,
+            MpegVersion::Mpeg25 => base_sample_rate / 4,
+        };
+
+        Some(MpegFrameHeader {
+            version,
+            layer,
+            bitrate: bitrates[bitrate_index],
+            sample_rate,
+            padding: (bytes[2] >> 1) & 1 == 1,
+        })
+    }
+
+    pub fn samples_per_frame(&self) -> u32 {
+        match (self.layer, self.version) {
+            (MpegLayer::Layer1, _) => 384,
+            (MpegLayer::Layer2, _) => 1152,
+            (MpegLayer::Layer3, MpegVersion::Mpeg1) => 1152,
+            (MpegLayer::Layer3, _) => 576,
+        }
+    }
+
+    /// Length of the whole frame, header included, in bytes.
+    pub fn frame_length(&self) -> u32 {
+        let bitrate = self.bitrate * 1000;
+        let padding = self.padding as u32;
+        match self.layer {
+            MpegLayer::Layer1 => (12 * bitrate / self.sample_rate + padding) * 4,
+            _ => self.samples_per_frame() / 8 * bitrate / self.sample_rate + padding,
+        }
+    }
+}
+
+/// An MPEG audio frame found while scanning a stream.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MpegFrame {
+    /// Position of the frame's first byte, relative to where the scan started.
+    pub offset: u64,
+    pub header: MpegFrameHeader,
+}
+
+impl MpegFrame {
+    /// in milliseconds
+    pub fn duration(&self) -> f64 {
+        self.header.samples_per_frame() as f64 * 1000.0 / self.header.sample_rate as f64
+    }
+}
+
+/// Reads the whole stream, collecting every MPEG audio frame in it. The reader should be
+/// positioned right after the ID3v2 tag. Bytes that don't belong to a frame, such as an ID3v1 tag,
+/// are skipped.
+pub fn scan_frames(reader: &mut impl Read) -> std::io::Result<Vec<MpegFrame>> {
+    let mut frames = vec![];
+    let mut offset = 0u64;
+    let mut header_bytes = [0u8; 4];
+
+    if !read_or_eof(reader, &mut header_bytes)? {
+        return Ok(frames);
+    }
+
+    loop {
+        let Some(header) = MpegFrameHeader::parse(header_bytes) else {
+            // Resynchronise one byte at a time
+            header_bytes.rotate_left(1);
+            if !read_or_eof(reader, &mut header_bytes[3..])? {
+                break;
+            }
+            offset += 1;
+            continue;
+        };
+
+        let body_length = header.frame_length() as u64 - 4;
+        let skipped = std::io::copy(&mut reader.take(body_length), &mut std::io::sink())?;
+        if skipped < body_length {
+            break;
+        }
+        frames.push(MpegFrame { offset, header });
+        offset += body_length + 4;
+
+        if !read_or_eof(reader, &mut header_bytes)? {
+            break;
+        }
+    }
+
+    Ok(frames)
+}
+
+fn read_or_eof(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<bool> {
+    match reader.read_exact(buffer) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn scan_with_junk() {
+        // MPEG 1 Layer III, 128kbit/s, 44100Hz, no padding: 417 bytes per frame
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x00];
+        frame.resize(417, 0);
+
+        let mut bytes = vec![0x12, 0x34];
+        bytes.extend(&frame);
+        bytes.extend(&frame);
+        bytes.extend(b"TAG");
+
+        let frames = scan_frames(&mut Cursor::new(bytes)).unwrap();
+        let offsets: Vec<u64> = frames.iter().map(|frame| frame.offset).collect();
+        assert_eq!(offsets, vec![2, 419]);
+        assert_eq!(frames[0].header.frame_length(), 417);
+        assert_eq!(frames[0].header.samples_per_frame(), 1152);
+    }
+}
@@ -0,0 +1,76 @@
+use tokio::io::{self, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::{
+    header::{Header, HeaderFlagType, HeaderReadError},
+    read_options::ReadOptions,
+    Tag, TagReadError,
+};
+
+impl Tag {
+    /// Same as [`Tag::read`], for readers of an async runtime. Only the bytes of the tag are
+    /// buffered, the reader is left right after it.
+    pub async fn read_async(reader: &mut (impl AsyncRead + Unpin)) -> Result<Tag, TagReadError> {
+        Tag::read_async_with_options(reader, ReadOptions::default()).await
+    }
+
+    /// Same as [`Tag::read_with_options`], for readers of an async runtime.
+    pub async fn read_async_with_options(
+        reader: &mut (impl AsyncRead + Unpin),
+        options: ReadOptions,
+    ) -> Result<Tag, TagReadError> {
+        let mut bytes = vec![0; 10];
+        reader
+            .read_exact(&mut bytes)
+            .await
+            .map_err(|_| HeaderReadError::NotEnoughBytes)?;
+        let header = Header::read(&mut &bytes[..])?;
+
+        let mut rest = header.tag_size() as u64;
+        if header.is_flag_set(&HeaderFlagType::FooterPresent) {
+            rest += header.bytes_size() as u64;
+        }
+        // A truncated tag is reported by the parser, as for sync readers
+        let _ = reader.take(rest).read_to_end(&mut bytes).await;
+
+        Tag::read_with_options(&mut &bytes[..], options)
+    }
+
+    /// Writes the whole tag, as [`Tag::to_bytes`] returns it.
+    pub async fn write_async(&self, writer: &mut (impl AsyncWrite + Unpin)) -> io::Result<()> {
+        writer.write_all(&self.to_bytes()).await?;
+        writer.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::{builder::TagBuilder, frame::FrameReadError, test_utils::frame};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn same_as_sync() {
+        let tag = TagBuilder::new()
+            .footer(true)
+            .frame(frame(b"TIT2", b"\x03Title"))
+            .build()
+            .unwrap();
+        let mut bytes = vec![];
+        tag.write_async(&mut bytes).await.unwrap();
+        assert_eq!(bytes, tag.to_bytes());
+        bytes.extend_from_slice(b"audio");
+
+        let mut reader = &bytes[..];
+        let read_tag = Tag::read_async(&mut reader).await.unwrap();
+        assert_eq!(read_tag, Tag::read(&mut &bytes[..]).unwrap());
+        assert_eq!(reader, b"audio");
+
+        assert_eq!(
+            Tag::read_async(&mut &bytes[..15]).await,
+            Err(TagReadError::FrameReadError(
+                1,
+                FrameReadError::NotEnoughBytes
+            ))
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use thiserror::Error;
+
+use super::{
+    extended_header::{ExtendedHeader, TagRestrictions, TextEncodingRestrictions},
+    frame::{frame_id::FrameID, Frame},
+    frame_access::FrameInsertError,
+    header::{Header, HeaderFlagType},
+    Tag,
+};
+
+/// Creates a tag from nothing. The combination of version, flags and frames is only checked
+/// by [`TagBuilder::build`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct TagBuilder {
+    major_version: u8,
+    padding: u32,
+    experimental: bool,
+    footer: bool,
+    update: bool,
+    crc: bool,
+    restrictions: Option<TagRestrictions>,
+    frames: Vec<Frame>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum TagBuildError {
+    #[error("Only ID3v2.3 and ID3v2.4 tags can be built, not ID3v2.{0}")]
+    UnsupportedVersion(u8),
+    #[error("Footers only exist since ID3v2.4")]
+    FooterNotSupported,
+    #[error("A tag with a footer must not have padding")]
+    FooterWithPadding,
+    #[error("ID3v2.3 extended headers can only hold a CRC")]
+    ExtendedHeaderNotSupported,
+    #[error("Frame {0} can't be added: {1}")]
+    FrameInsertError(u32, FrameInsertError),
+    #[error("The tag restrictions allow {max} frames, but there are {count}")]
+    TooManyFrames { max: usize, count: usize },
+    #[error("The tag restrictions allow {max} bytes, but the tag has {size}")]
+    TagTooLarge { max: u32, size: u32 },
+    #[error("The tag restrictions only allow ISO-8859-1 and UTF-8, but the {0} frame uses UTF-16")]
+    ForbiddenTextEncoding(FrameID),
+}
+
+/// Whether the content of the frame starts with a text encoding byte.
+fn has_text_encoding(frame_id: &FrameID) -> bool {
+    matches!(
+        frame_id.bytes(),
+        [b'T', ..]
+            | b"WXXX"
+            | b"COMM"
+            | b"USLT"
+            | b"SYLT"
+            | b"APIC"
+            | b"GEOB"
+            | b"USER"
+            | b"OWNE"
+            | b"COMR"
+            | b"IPLS"
+    )
+}
+
+impl Default for TagBuilder {
+    fn default() -> Self {
+        Self {
+            major_version: 4,
+            padding: 0,
+            experimental: false,
+            footer: false,
+            update: false,
+            crc: false,
+            restrictions: None,
+            frames: vec![],
+        }
+    }
+}
+
+impl TagBuilder {
+    /// A builder of an empty ID3v2.4 tag, without padding nor flags.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The 3 in ID3v2.3.0
+    pub fn version(mut self, major_version: u8) -> Self {
+        self.major_version = major_version;
+        self
+    }
+
+    /// in bytes
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn experimental(mut self, experimental: bool) -> Self {
+        self.experimental = experimental;
+        self
+    }
+
+    /// Only allowed on ID3v2.4 tags without padding
+    pub fn footer(mut self, footer: bool) -> Self {
+        self.footer = footer;
+        self
+    }
+
+    /// Marks the tag as an update of a tag found earlier in the file or stream, which needs an
+    /// extended header.
+    pub fn update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Stores a CRC-32 of the frames and padding in an extended header. ID3v2.3 leaves the
+    /// padding out.
+    pub fn crc(mut self, crc: bool) -> Self {
+        self.crc = crc;
+        self
+    }
+
+    /// Stores the restrictions in an extended header. The tag size and text encoding ones are
+    /// checked when building.
+    pub fn restrictions(mut self, restrictions: TagRestrictions) -> Self {
+        self.restrictions = Some(restrictions);
+        self
+    }
+
+    pub fn frame(mut self, frame: Frame) -> Self {
+        self.frames.push(frame);
+        self
+    }
+
+    pub fn frames(mut self, frames: impl IntoIterator<Item = Frame>) -> Self {
+        self.frames.extend(frames);
+        self
+    }
+
+    pub fn build(self) -> Result<Tag, TagBuildError> {
+        if !matches!(self.major_version, 3 | 4) {
+            return Err(TagBuildError::UnsupportedVersion(self.major_version));
+        }
+        if self.footer && self.major_version != 4 {
+            return Err(TagBuildError::FooterNotSupported);
+        }
+        if self.footer && self.padding != 0 {
+            return Err(TagBuildError::FooterWithPadding);
+        }
+
+        let has_extended_header = self.update || self.crc || self.restrictions.is_some();
+        if (self.update || self.restrictions.is_some()) && self.major_version != 4 {
+            return Err(TagBuildError::ExtendedHeaderNotSupported);
+        }
+
+        let mut flags = vec![];
+        if has_extended_header {
+            flags.push(HeaderFlagType::ExtendedHeader);
+        }
+        if self.experimental {
+            flags.push(HeaderFlagType::ExperimentalIndicator);
+        }
+        if self.footer {
+            flags.push(HeaderFlagType::FooterPresent);
+        }
+
+        let mut tag = Tag {
+            header: Header::new(self.major_version, &flags),
+            extended_header: has_extended_header.then(|| match self.major_version {
+                3 => ExtendedHeader::new_v23(self.crc.then_some(0)),
+                _ => ExtendedHeader::new(
+                    self.update,
+                    self.crc.then_some(0),
+                    self.restrictions.clone(),
+                ),
+            }),
+            frames: vec![],
+            padding: self.padding,
+            footer: None,
+        };
+        for (index, frame) in self.frames.into_iter().enumerate() {
+            tag.insert(frame)
+                .map_err(|error| TagBuildError::FrameInsertError(index as u32 + 1, error))?;
+        }
+
+        let size = tag.to_bytes().len() as u32;
+        let footer_size = if self.footer { 10 } else { 0 };
+        tag.header = tag
+            .header
+            .with_tag_size(size - tag.header.bytes_size() - footer_size);
+
+        if let Some(restrictions) = &self.restrictions {
+            Self::check_restrictions(&tag, restrictions, size)?;
+        }
+
+        Ok(tag)
+    }
+
+    fn check_restrictions(
+        tag: &Tag,
+        restrictions: &TagRestrictions,
+        size: u32,
+    ) -> Result<(), TagBuildError> {
+        let size_restrictions = &restrictions.tag_size_restrictions;
+        if tag.frames.len() > size_restrictions.max_frames() {
+            return Err(TagBuildError::TooManyFrames {
+                max: size_restrictions.max_frames(),
+                count: tag.frames.len(),
+            });
+        }
+        if size > size_restrictions.max_size() {
+            return Err(TagBuildError::TagTooLarge {
+                max: size_restrictions.max_size(),
+                size,
+            });
+        }
+
+        if restrictions.text_encoding_restrictions == TextEncodingRestrictions::ISO88591OrUTF8 {
+            let utf16_frame = tag.frames.iter().find(|frame| {
+                let content = frame.content(4).unwrap_or(frame.data());
+                has_text_encoding(frame.frame_id()) && matches!(content.first(), Some(1 | 2))
+            });
+            if let Some(frame) = utf16_frame {
+                return Err(TagBuildError::ForbiddenTextEncoding(
+                    frame.frame_id().clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{extended_header::TagSizeRestrictions, test_utils::frame};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn build_and_read_back() {
+        let tag = TagBuilder::new()
+            .padding(20)
+            .crc(true)
+            .restrictions(TagRestrictions::default())
+            .frame(frame(b"TIT2", b"\x03Title"))
+            .frame(frame(b"TPE1", b"\x03Artist"))
+            .build()
+            .unwrap();
+        let bytes = tag.to_bytes();
+        assert_eq!(bytes.len() as u32, tag.bytes_size());
+
+        let mut reader = Cursor::new([bytes.clone(), b"audio".to_vec()].concat());
+        let read_tag = Tag::read(&mut reader).unwrap();
+        assert_eq!(reader.position(), bytes.len() as u64);
+        assert_eq!(read_tag.frames(), tag.frames());
+        assert_eq!(read_tag.padding(), 20);
+        assert_eq!(read_tag.header(), tag.header());
+
+        // After the header and the 14 bytes of extended header
+        let frames_and_padding = &bytes[24..];
+        let crc = read_tag.extended_header().unwrap().crc();
+        assert_eq!(crc, Some(crc32fast::hash(frames_and_padding)));
+        assert_eq!(read_tag.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn build_v23_and_footer() {
+        let long_text = [b"\x00".as_slice(), &[b'a'; 200]].concat();
+        let tag = TagBuilder::new()
+            .version(3)
+            .experimental(true)
+            .frame(frame(b"TIT2", &long_text))
+            .build()
+            .unwrap();
+        let read_tag = Tag::read(&mut Cursor::new(tag.to_bytes())).unwrap();
+        assert_eq!(read_tag, tag);
+        assert!(tag
+            .header()
+            .is_flag_set(&HeaderFlagType::ExperimentalIndicator));
+
+        let tag = TagBuilder::new().footer(true).build().unwrap();
+        let bytes = tag.to_bytes();
+        assert_eq!(&bytes[10..], b"3DI\x04\x00\x10\x00\x00\x00\x00");
+    }
+
+    #[test]
+    fn build_v23_with_crc() {
+        let tag = TagBuilder::new()
+            .version(3)
+            .padding(20)
+            .crc(true)
+            .frame(frame(b"TIT2", b"\x00Title"))
+            .build()
+            .unwrap();
+        let bytes = tag.to_bytes();
+        assert_eq!(bytes.len() as u32, tag.bytes_size());
+
+        // After the header, the 14 bytes of extended header hold the padding size and the CRC
+        assert_eq!(&bytes[10..20], b"\x00\x00\x00\x0A\x80\x00\x00\x00\x00\x14");
+        let frames = &bytes[24..bytes.len() - 20];
+        assert_eq!(&bytes[20..24], crc32fast::hash(frames).to_be_bytes());
+
+        let mut reader = Cursor::new([bytes.clone(), b"audio".to_vec()].concat());
+        let read_tag = Tag::read(&mut reader).unwrap();
+        assert_eq!(reader.position(), bytes.len() as u64);
+        assert_eq!(read_tag.frames(), tag.frames());
+        assert_eq!(read_tag.padding(), 20);
+        assert_eq!(read_tag.to_bytes(), bytes);
+        assert_eq!(Tag::parse(&bytes).unwrap().to_tag(), read_tag);
+    }
+
+    #[test]
+    fn invalid_combinations() {
+        assert_eq!(
+            TagBuilder::new().version(2).build(),
+            Err(TagBuildError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            TagBuilder::new().version(3).footer(true).build(),
+            Err(TagBuildError::FooterNotSupported)
+        );
+        assert_eq!(
+            TagBuilder::new().footer(true).padding(10).build(),
+            Err(TagBuildError::FooterWithPadding)
+        );
+        assert_eq!(
+            TagBuilder::new().version(3).update(true).build(),
+            Err(TagBuildError::ExtendedHeaderNotSupported)
+        );
+        assert_eq!(
+            TagBuilder::new()
+                .frame(frame(b"TIT2", b"\x00Title"))
+                .frame(frame(b"TIT2", b"\x00Other title"))
+                .build(),
+            Err(TagBuildError::FrameInsertError(
+                2,
+                FrameInsertError::OnlyOnePerTag(FrameID::try_from(*b"TIT2").unwrap())
+            ))
+        );
+
+        let restrictions = TagRestrictions {
+            tag_size_restrictions: TagSizeRestrictions::Max32Frames4KB,
+            text_encoding_restrictions: TextEncodingRestrictions::ISO88591OrUTF8,
+            ..TagRestrictions::default()
+        };
+        assert_eq!(
+            TagBuilder::new()
+                .restrictions(restrictions.clone())
+                .padding(4096)
+                .build(),
+            Err(TagBuildError::TagTooLarge {
+                max: 4096,
+                size: 4096 + 10 + 8
+            })
+        );
+        assert_eq!(
+            TagBuilder::new()
+                .restrictions(restrictions)
+                .frame(frame(b"TIT2", b"\x01\xFF\xFEa\x00"))
+                .build(),
+            Err(TagBuildError::ForbiddenTextEncoding(
+                FrameID::try_from(*b"TIT2").unwrap()
+            ))
+        );
+    }
+}
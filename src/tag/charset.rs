@@ -0,0 +1,456 @@
+use crate::utils::{latin1_to_string, string_to_latin1};
+
+use super::{
+    encoding::Encoding,
+    frame::{comment::Comment, text_information::TextInformation},
+    Tag,
+};
+
+/// Code pages that text claiming to be Latin-1 is often actually encoded in.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LegacyCharset {
+    /// Central European
+    Windows1250,
+    /// Cyrillic
+    Windows1251,
+    /// Western European, Latin-1 with printable characters in place of some control codes
+    Windows1252,
+    /// Russian
+    Koi8R,
+    /// Japanese
+    ShiftJis,
+    /// Simplified Chinese
+    Gbk,
+    /// Traditional Chinese
+    Big5,
+    /// Korean
+    EucKr,
+}
+
+/// How text claiming to be Latin-1 should be decoded.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CharsetGuess {
+    /// With the charset [`LegacyCharset::guess`] finds, if any
+    Detect,
+    Charset(LegacyCharset),
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Cjk,
+    Hangul,
+    Other,
+}
+
+fn script(char: char) -> Script {
+    match char {
+        'A'..='Z' | 'a'..='z' | '\u{C0}'..='\u{24F}' | '\u{1E00}'..='\u{1EFF}' => Script::Latin,
+        '\u{400}'..='\u{4FF}' => Script::Cyrillic,
+        '\u{3040}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}' => Script::Cjk,
+        '\u{AC00}'..='\u{D7A3}' => Script::Hangul,
+        _ => Script::Other,
+    }
+}
+
+impl LegacyCharset {
+    /// The charsets [`LegacyCharset::guess`] chooses from, the first ones winning ties.
+    pub const DETECTED: [LegacyCharset; 6] = [
+        LegacyCharset::Windows1252,
+        LegacyCharset::Windows1251,
+        LegacyCharset::Koi8R,
+        LegacyCharset::Windows1250,
+        LegacyCharset::ShiftJis,
+        LegacyCharset::Gbk,
+    ];
+
+    fn encoding(&self) -> &'static encoding_rs::Encoding {
+        match self {
+            LegacyCharset::Windows1250 => encoding_rs::WINDOWS_1250,
+            LegacyCharset::Windows1251 => encoding_rs::WINDOWS_1251,
+            LegacyCharset::Windows1252 => encoding_rs::WINDOWS_1252,
+            LegacyCharset::Koi8R => encoding_rs::KOI8_R,
+            LegacyCharset::ShiftJis => encoding_rs::SHIFT_JIS,
+            LegacyCharset::Gbk => encoding_rs::GBK,
+            LegacyCharset::Big5 => encoding_rs::BIG5,
+            LegacyCharset::EucKr => encoding_rs::EUC_KR,
+        }
+    }
+
+    /// The scripts of the letters the charset holds besides ASCII.
+    fn scripts(&self) -> &'static [Script] {
+        match self {
+            LegacyCharset::Windows1250 | LegacyCharset::Windows1252 => &[Script::Latin],
+            LegacyCharset::Windows1251 | LegacyCharset::Koi8R => &[Script::Cyrillic],
+            LegacyCharset::ShiftJis | LegacyCharset::Gbk | LegacyCharset::Big5 => &[Script::Cjk],
+            LegacyCharset::EucKr => &[Script::Hangul, Script::Cjk],
+        }
+    }
+
+    /// Decodes the bytes, unless they aren't valid in this charset.
+    pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+        self.encoding()
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|string| string.into_owned())
+    }
+
+    /// The charset most likely used to encode the bytes, when it isn't Latin-1. Pure ASCII and
+    /// proper Latin-1 text give none.
+    pub fn guess(bytes: &[u8]) -> Option<LegacyCharset> {
+        if bytes.is_ascii() {
+            return None;
+        }
+        let latin1 = latin1_to_string(bytes);
+        let latin1_score = plausibility(&latin1, &[Script::Latin]);
+
+        let mut best: Option<(LegacyCharset, i64)> = None;
+        for charset in LegacyCharset::DETECTED {
+            let Some(string) = charset.decode(bytes) else {
+                continue;
+            };
+            if string == latin1 {
+                continue;
+            }
+            let score = plausibility(&string, charset.scripts()) + charset.byte_score(bytes);
+            if score > latin1_score && best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((charset, score));
+            }
+        }
+        best.map(|(charset, _)| charset)
+    }
+
+    /// Favours the double byte characters used the most. Their lead byte is in the upper part of
+    /// the range, and the most common Chinese characters come first in it.
+    fn byte_score(&self, bytes: &[u8]) -> i64 {
+        if !matches!(self, LegacyCharset::Gbk | LegacyCharset::Big5) {
+            return 0;
+        }
+        let mut score = 0;
+        let mut index = 0;
+        while let Some(byte) = bytes.get(index) {
+            if byte.is_ascii() {
+                index += 1;
+                continue;
+            }
+            score += match (self, byte) {
+                (_, 0x80..=0xA0) => -3,
+                (LegacyCharset::Gbk, 0xB0..=0xD7) | (LegacyCharset::Big5, 0xA4..=0xC6) => 1,
+                _ => -1,
+            };
+            index += 2;
+        }
+        score
+    }
+}
+
+/// How much the string looks like natural text written with the given scripts. Every character
+/// out of them, and every word mixing scripts or cases, lowers the score.
+fn plausibility(string: &str, scripts: &[Script]) -> i64 {
+    let mut score = 0;
+    for char in string.chars().filter(|char| !char.is_ascii()) {
+        score += match script(char) {
+            // These take 2 bytes, where letters of the other scripts only take one
+            Script::Cjk | Script::Hangul if scripts.contains(&script(char)) => 2,
+            script if scripts.contains(&script) && char.is_alphabetic() => 1,
+            _ => match char {
+                '\u{2000}'..='\u{206F}' | '€' | '\u{A0}' => 0,
+                // Half width katakana, which are rarely used
+                '\u{FF61}'..='\u{FF9F}' => -1,
+                _ => -2,
+            },
+        };
+    }
+
+    for word in string.split(|char: char| !char.is_alphabetic()) {
+        let chars = word.chars().collect::<Vec<char>>();
+        for pair in chars.windows(2) {
+            let mixed_scripts = script(pair[0]) != script(pair[1]);
+            let mixed_cases = pair[0].is_lowercase() && pair[1].is_uppercase();
+            if mixed_scripts || mixed_cases {
+                score -= 2;
+            }
+        }
+
+        // Latin words are seldom only made of accented letters
+        let accented = chars
+            .iter()
+            .filter(|char| !char.is_ascii() && script(**char) == Script::Latin)
+            .count();
+        if accented >= 3 && !chars.iter().any(char::is_ascii) {
+            score -= 2 * accented as i64;
+        }
+    }
+    score
+}
+
+impl CharsetGuess {
+    /// Decodes text read as Latin-1 with the guessed charset. Nothing is returned when the
+    /// guess is Latin-1 itself, or doesn't fit the text.
+    pub fn redecode(&self, latin1: &str) -> Option<String> {
+        if !Encoding::Latin1.can_encode(latin1) {
+            return None;
+        }
+        let bytes = string_to_latin1(latin1);
+        let charset = match self {
+            CharsetGuess::Detect => LegacyCharset::guess(&bytes)?,
+            CharsetGuess::Charset(charset) => *charset,
+        };
+        charset.decode(&bytes).filter(|string| string != latin1)
+    }
+}
+
+impl Encoding {
+    /// Same as [`Encoding::make_string`], except Latin-1 strings are decoded with the guessed
+    /// charset when it fits.
+    pub fn make_string_with_charset(&self, bytes: &[u8], guess: &CharsetGuess) -> String {
+        let string = self.make_string(bytes);
+        match self {
+            Encoding::Latin1 => guess.redecode(&string).unwrap_or(string),
+            _ => string,
+        }
+    }
+}
+
+impl Tag {
+    /// The Latin-1 strings of the text, comment and lyrics frames.
+    fn latin1_strings(&self) -> Vec<String> {
+        let major_version = self.header.major_version();
+        let mut strings = vec![];
+        for frame in &self.frames {
+            let Ok(content) = frame.content(major_version) else {
+                continue;
+            };
+            match frame.frame_id().bytes() {
+                [b'T', ..] => {
+                    if let Ok(text) = TextInformation::parse(content) {
+                        if *text.encoding() == Encoding::Latin1 {
+                            strings.extend(text.strings().iter().cloned());
+                        }
+                    }
+                }
+                b"COMM" | b"USLT" => {
+                    if let Ok(comment) = Comment::parse(content) {
+                        if comment.encoding == Encoding::Latin1 {
+                            strings.extend([comment.description, comment.text]);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        strings
+    }
+
+    /// The charset the Latin-1 text of the tag was most likely encoded in, judging from all of
+    /// it at once.
+    pub fn guess_charset(&self) -> Option<LegacyCharset> {
+        LegacyCharset::guess(&string_to_latin1(&self.latin1_strings().join(" ")))
+    }
+
+    /// Decodes the Latin-1 text of the text, comment and lyrics frames with the guessed charset,
+    /// and stores it back in an encoding that can hold it. Frames that are compressed,
+    /// encrypted or unsynchronised are left as they are. Returns how many frames changed.
+    pub fn repair_charset(&mut self, guess: &CharsetGuess) -> usize {
+        let guess = match guess {
+            CharsetGuess::Detect => match self.guess_charset() {
+                Some(charset) => CharsetGuess::Charset(charset),
+                None => return 0,
+            },
+            guess => *guess,
+        };
+
+        let major_version = self.header.major_version();
+        let mut repaired = 0;
+        for index in 0..self.frames.len() {
+            let frame = &self.frames[index];
+            let flags = frame.flags(major_version);
+            if flags.compression || flags.encryption || flags.unsynchronisation {
+                continue;
+            }
+            let Ok(mut flag_data) = frame.flag_data(major_version) else {
+                continue;
+            };
+            let content = &frame.data()[flag_data.size..];
+
+            let repaired_content = match frame.frame_id().bytes() {
+                [b'T', ..] => self.repair_text_information(content, &guess),
+                b"COMM" | b"USLT" => self.repair_comment(content, &guess),
+                _ => None,
+            };
+            if let Some(repaired_content) = repaired_content {
+                // Only set on uncompressed frames by the ID3v2.4 data length indicator
+                if flag_data.data_length.is_some() {
+                    flag_data.data_length = Some(repaired_content.len() as u32);
+                }
+                let mut data = flag_data.to_bytes(major_version);
+                data.extend(repaired_content);
+                self.frames[index] = self.frames[index].with_data(data);
+                repaired += 1;
+            }
+        }
+        repaired
+    }
+
+    fn repair_text_information(&self, content: &[u8], guess: &CharsetGuess) -> Option<Vec<u8>> {
+        let text = TextInformation::parse(content).ok()?;
+        if *text.encoding() != Encoding::Latin1 {
+            return None;
+        }
+        let strings = text
+            .strings()
+            .iter()
+            .map(|string| guess.redecode(string))
+            .collect::<Vec<Option<String>>>();
+        if strings.iter().all(Option::is_none) {
+            return None;
+        }
+
+        let strings = strings
+            .into_iter()
+            .zip(text.strings())
+            .map(|(repaired, string)| repaired.unwrap_or_else(|| string.clone()))
+            .collect::<Vec<String>>();
+        // Left as they are when the tag restrictions forbid the encoding they'd need
+        let encoding = self
+            .text_encoding(&strings.iter().map(String::as_str).collect::<Vec<_>>())
+            .ok()?;
+        Some(TextInformation::new(encoding, strings).to_bytes())
+    }
+
+    fn repair_comment(&self, content: &[u8], guess: &CharsetGuess) -> Option<Vec<u8>> {
+        let comment = Comment::parse(content).ok()?;
+        if comment.encoding != Encoding::Latin1 {
+            return None;
+        }
+        let description = guess.redecode(&comment.description);
+        let text = guess.redecode(&comment.text);
+        if description.is_none() && text.is_none() {
+            return None;
+        }
+
+        let description = description.unwrap_or(comment.description);
+        let text = text.unwrap_or(comment.text);
+        Some(
+            Comment {
+                encoding: self.text_encoding(&[&description, &text]).ok()?,
+                language: comment.language,
+                description,
+                text,
+            }
+            .to_bytes(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::{
+        builder::TagBuilder,
+        frame::{frame_id::FrameID, Frame},
+        metadata::Metadata,
+        test_utils::flagged_frame,
+    };
+    use crate::utils::write_syncsafe_integer;
+    use pretty_assertions::assert_eq;
+
+    fn guess(string: &str, charset: LegacyCharset) -> Option<LegacyCharset> {
+        let (bytes, _, unmappable) = charset.encoding().encode(string);
+        assert!(!unmappable, "{string} can't be encoded in {charset:?}");
+        LegacyCharset::guess(&bytes)
+    }
+
+    #[test]
+    fn latin1_is_left_alone() {
+        assert_eq!(LegacyCharset::guess(b"Plain ASCII"), None);
+        assert_eq!(LegacyCharset::guess(&string_to_latin1("Café Noël")), None);
+        assert_eq!(
+            LegacyCharset::guess(&string_to_latin1("Motörhead - Süß")),
+            None
+        );
+        assert_eq!(
+            LegacyCharset::guess(&string_to_latin1("Sigur Rós - Ágætis byrjun")),
+            None
+        );
+    }
+
+    #[test]
+    fn detected_charsets() {
+        use LegacyCharset::*;
+        assert_eq!(guess("Привет, мир", Windows1251), Some(Windows1251));
+        assert_eq!(guess("Кино - Группа крови", Windows1251), Some(Windows1251));
+        assert_eq!(guess("Машина времени", Koi8R), Some(Koi8R));
+        assert_eq!(guess("Łódź, Kraków", Windows1250), Some(Windows1250));
+        assert_eq!(guess("Šťastný den", Windows1250), Some(Windows1250));
+        assert_eq!(guess("東京事変", ShiftJis), Some(ShiftJis));
+        assert_eq!(guess("こんにちは", ShiftJis), Some(ShiftJis));
+        assert_eq!(guess("中文歌曲", Gbk), Some(Gbk));
+        assert_eq!(guess("“Quoted”", Windows1252), Some(Windows1252));
+    }
+
+    #[test]
+    fn redecode() {
+        let mojibake = latin1_to_string(&encoding_rs::WINDOWS_1251.encode("Кино").0);
+        assert_eq!(
+            CharsetGuess::Detect.redecode(&mojibake),
+            Some(String::from("Кино"))
+        );
+        assert_eq!(
+            CharsetGuess::Charset(LegacyCharset::Windows1251).redecode(&mojibake),
+            Some(String::from("Кино"))
+        );
+        assert_eq!(CharsetGuess::Detect.redecode("Café"), None);
+        assert_eq!(CharsetGuess::Detect.redecode("Кино"), None);
+
+        let bytes = encoding_rs::SHIFT_JIS.encode("東京").0;
+        let guess = CharsetGuess::Charset(LegacyCharset::ShiftJis);
+        assert_eq!(
+            Encoding::Latin1.make_string_with_charset(&bytes, &guess),
+            "東京"
+        );
+        assert_eq!(
+            Encoding::UTF8.make_string_with_charset(b"abc", &guess),
+            "abc"
+        );
+    }
+
+    #[test]
+    fn repair_tag() {
+        let latin1_frame = |id: &[u8; 4], text: &str| {
+            let mut data = vec![0];
+            data.extend(encoding_rs::WINDOWS_1251.encode(text).0.iter());
+            Frame::new(FrameID::try_from(*id).unwrap(), data)
+        };
+        // Grouped with 0x90, and with a data length indicator
+        let artist = latin1_frame(b"TPE1", "Кино");
+        let flag_data = [
+            &[0x90][..],
+            &write_syncsafe_integer(artist.data().len() as u32),
+            artist.data(),
+        ]
+        .concat();
+        let mut tag = TagBuilder::new()
+            .frame(latin1_frame(b"TIT2", "Группа крови"))
+            .frame(flagged_frame(b"TPE1", 0b0100_0001, &flag_data))
+            .frame(latin1_frame(b"TALB", "Album"))
+            .build()
+            .unwrap();
+
+        assert_eq!(tag.guess_charset(), Some(LegacyCharset::Windows1251));
+        assert_eq!(tag.repair_charset(&CharsetGuess::Detect), 2);
+        assert_eq!(tag.title(), Some(String::from("Группа крови")));
+        assert_eq!(tag.artist(), Some(String::from("Кино")));
+        assert_eq!(tag.album(), Some(String::from("Album")));
+        assert_eq!(tag.get(b"TIT2").unwrap().data()[0], 3);
+
+        let artist = tag.get(b"TPE1").unwrap();
+        let flag_data = artist.flag_data(4).unwrap();
+        assert_eq!(flag_data.group_symbol, Some(0x90));
+        assert_eq!(
+            flag_data.data_length,
+            Some(artist.content(4).unwrap().len() as u32)
+        );
+        assert_eq!(tag.repair_charset(&CharsetGuess::Detect), 0);
+    }
+}
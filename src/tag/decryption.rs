@@ -0,0 +1,215 @@
+use thiserror::Error;
+
+use super::{
+    frame::{frame_id::FrameID, registration::EncryptionMethodRegistration},
+    Tag, TagReadError,
+};
+
+#[derive(PartialEq, Clone, Debug, Error)]
+#[error("{0}")]
+pub struct FrameDecryptionError(pub String);
+
+/// Decrypts the content of frames encrypted with a method registered in an ENCR frame.
+pub trait FrameDecryptor {
+    /// Receives the encrypted content, without the group and method symbols, and returns the
+    /// plaintext that will be parsed in its place.
+    fn decrypt(
+        &self,
+        frame_id: &FrameID,
+        registration: &EncryptionMethodRegistration,
+        encrypted: &[u8],
+    ) -> Result<Vec<u8>, FrameDecryptionError>;
+}
+
+impl<F> FrameDecryptor for F
+where
+    F: Fn(&FrameID, &EncryptionMethodRegistration, &[u8]) -> Result<Vec<u8>, FrameDecryptionError>,
+{
+    fn decrypt(
+        &self,
+        frame_id: &FrameID,
+        registration: &EncryptionMethodRegistration,
+        encrypted: &[u8],
+    ) -> Result<Vec<u8>, FrameDecryptionError> {
+        self(frame_id, registration, encrypted)
+    }
+}
+
+struct RegisteredDecryptor {
+    owner_identifier: String,
+    method_symbol: Option<u8>,
+    decryptor: Box<dyn FrameDecryptor + Send + Sync>,
+}
+
+/// Decryptors registered by the owner identifier of their ENCR frame, used while reading a tag
+/// through [`crate::tag::read_options::ReadOptions::decryptors`].
+#[derive(Default)]
+pub struct FrameDecryptors {
+    decryptors: Vec<RegisteredDecryptor>,
+}
+
+impl FrameDecryptors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles every method registered by the given owner.
+    pub fn register(
+        &mut self,
+        owner_identifier: impl Into<String>,
+        decryptor: impl FrameDecryptor + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.decryptors.push(RegisteredDecryptor {
+            owner_identifier: owner_identifier.into(),
+            method_symbol: None,
+            decryptor: Box::new(decryptor),
+        });
+        self
+    }
+
+    /// Handles only the method registered by the given owner with the given symbol. Takes
+    /// precedence over decryptors registered for the whole owner.
+    pub fn register_method(
+        &mut self,
+        owner_identifier: impl Into<String>,
+        method_symbol: u8,
+        decryptor: impl FrameDecryptor + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.decryptors.push(RegisteredDecryptor {
+            owner_identifier: owner_identifier.into(),
+            method_symbol: Some(method_symbol),
+            decryptor: Box::new(decryptor),
+        });
+        self
+    }
+
+    fn find(
+        &self,
+        registration: &EncryptionMethodRegistration,
+    ) -> Option<&(dyn FrameDecryptor + Send + Sync)> {
+        let owned = self
+            .decryptors
+            .iter()
+            .filter(|decryptor| decryptor.owner_identifier == registration.owner_identifier);
+        owned
+            .clone()
+            .find(|decryptor| decryptor.method_symbol == Some(registration.method_symbol))
+            .or_else(|| {
+                owned
+                    .clone()
+                    .find(|decryptor| decryptor.method_symbol.is_none())
+            })
+            .map(|decryptor| decryptor.decryptor.as_ref())
+    }
+}
+
+impl Tag {
+    /// Replaces the content of encrypted frames with their plaintext, using the given
+    /// decryptors. Returns how many frames were decrypted.
+    pub fn decrypt_frames(&mut self, decryptors: &FrameDecryptors) -> Result<usize, TagReadError> {
+        let major_version = self.header.major_version();
+        let registrations = self.encryption_method_registrations();
+        let mut decrypted = 0;
+
+        for (index, frame) in self.frames.iter_mut().enumerate() {
+            let frame_read_error = |err| TagReadError::FrameReadError(index as u32 + 1, err);
+
+            let Some(method_symbol) = frame
+                .flag_data(major_version)
+                .map_err(frame_read_error)?
+                .encryption_method
+            else {
+                continue;
+            };
+            let Some(registration) = registrations
+                .iter()
+                .find(|registration| registration.method_symbol == method_symbol)
+            else {
+                continue;
+            };
+            let Some(decryptor) = decryptors.find(registration) else {
+                continue;
+            };
+
+            let encrypted = frame.content(major_version).map_err(frame_read_error)?;
+            let plaintext = decryptor
+                .decrypt(frame.frame_id(), registration, encrypted)
+                .map_err(|err| TagReadError::FrameDecryptionError(index as u32 + 1, err))?;
+            *frame = frame
+                .with_decrypted_content(major_version, &plaintext)
+                .map_err(frame_read_error)?;
+            decrypted += 1;
+        }
+
+        Ok(decrypted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::{
+        read_options::ReadOptions,
+        test_utils::{flagged_frame, frame, tag_bytes},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn xor(
+        _: &FrameID,
+        registration: &EncryptionMethodRegistration,
+        encrypted: &[u8],
+    ) -> Result<Vec<u8>, FrameDecryptionError> {
+        let key = registration.encryption_data[0];
+        Ok(encrypted.iter().map(|byte| byte ^ key).collect())
+    }
+
+    #[test]
+    fn decrypt_registered_owner() {
+        let plaintext = b"\x00Licensed title";
+        let encrypted = plaintext
+            .iter()
+            .map(|byte| byte ^ 0x2A)
+            .collect::<Vec<u8>>();
+
+        let bytes = tag_bytes(vec![
+            // Grouped with 0x90 and encrypted with 0x81
+            flagged_frame(
+                b"TIT2",
+                0b0100_0100,
+                &[&[0x90, 0x81], &encrypted[..]].concat(),
+            ),
+            // Encrypted by an owner with no decryptor
+            flagged_frame(b"TALB", 0b0000_0100, b"\x82opaque"),
+            frame(b"ENCR", b"drm@example.com\x00\x81\x2A"),
+            frame(b"ENCR", b"other@example.com\x00\x82"),
+        ]);
+
+        let mut decryptors = FrameDecryptors::new();
+        decryptors.register("drm@example.com", xor);
+        let options = ReadOptions {
+            decryptors: Some(decryptors),
+            ..ReadOptions::default()
+        };
+        let tag = Tag::read_with_options(&mut &bytes[..], options).unwrap();
+
+        let title = &tag.frames[0];
+        assert!(!title.flags(4).encryption);
+        assert_eq!(title.flag_data(4).unwrap().group_symbol, Some(0x90));
+        assert_eq!(title.content(4), Ok(&plaintext[..]));
+
+        let album = &tag.frames[1];
+        assert!(album.flags(4).encryption);
+        assert_eq!(album.data(), b"\x82opaque");
+
+        // The ENCR frames are still read when filtered out
+        let mut decryptors = FrameDecryptors::new();
+        decryptors.register("drm@example.com", xor);
+        let options = ReadOptions {
+            frame_filter: Some(Box::new(|id| id.bytes() == b"TIT2")),
+            decryptors: Some(decryptors),
+            ..ReadOptions::default()
+        };
+        let tag = Tag::read_with_options(&mut &bytes[..], options).unwrap();
+        assert_eq!(tag.frames[0].content(4), Ok(&plaintext[..]));
+    }
+}
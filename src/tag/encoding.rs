@@ -1,6 +1,8 @@
 use thiserror::Error;
 
-use crate::{find_substring, utils::latin1_to_string};
+use crate::utils::{latin1_to_string, string_to_latin1};
+
+use super::extended_header::TextEncodingRestrictions;
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum ByteOrder {
@@ -26,11 +28,15 @@ pub enum EncodingError {
     MissingBOM,
     #[error("Invalid Byte Order Mark")]
     InvalidBOM(u8, u8),
+    #[error("The character {0:?} can't be encoded in ISO-8859-1")]
+    UnrepresentableCharacter(char),
+    #[error("ID3v2.{0} tags need UTF-16 for this text, which the tag restrictions forbid")]
+    ForbiddenEncoding(u8),
 }
 
 impl Encoding {
-    pub fn extract_from_vec(vector: &Vec<u8>) -> Result<Self, EncodingError> {
-        let Some(encoding_byte) = vector.get(0) else {
+    pub fn extract_from_vec(vector: &[u8]) -> Result<Self, EncodingError> {
+        let Some(encoding_byte) = vector.first() else {
             return Err(EncodingError::MissingEncoding);
         };
         match encoding_byte {
@@ -51,6 +57,111 @@ impl Encoding {
         }
     }
 
+    /// Parses a lone encoding byte, for frames where the first string is not the one carrying the
+    /// BOM. UTF-16 defaults to little endian, which is what [`Encoding::encode_string`] writes.
+    pub fn from_encoding_byte(byte: u8) -> Result<Self, EncodingError> {
+        match byte {
+            0 => Ok(Encoding::Latin1),
+            1 => Ok(Encoding::UTF16(ByteOrder::LittleEndian)),
+            2 => Ok(Encoding::UTF16BE),
+            3 => Ok(Encoding::UTF8),
+            _ => Err(EncodingError::UnknownEncoding(byte)),
+        }
+    }
+
+    /// The most compact encoding a tag of the given version may hold the strings in: Latin-1
+    /// when it can represent them, and otherwise UTF-8 since ID3v2.4, or UTF-16 with a BOM
+    /// before.
+    pub fn select(
+        major_version: u8,
+        restrictions: Option<&TextEncodingRestrictions>,
+        strings: &[&str],
+    ) -> Result<Self, EncodingError> {
+        if strings
+            .iter()
+            .all(|string| Encoding::Latin1.can_encode(string))
+        {
+            return Ok(Encoding::Latin1);
+        }
+        if major_version >= 4 {
+            return Ok(Encoding::UTF8);
+        }
+        match restrictions {
+            Some(TextEncodingRestrictions::ISO88591OrUTF8) => {
+                Err(EncodingError::ForbiddenEncoding(major_version))
+            }
+            _ => Ok(Encoding::UTF16(ByteOrder::LittleEndian)),
+        }
+    }
+
+    /// Whether every character of the string can be encoded. Only Latin-1 lacks some.
+    pub fn can_encode(&self, string: &str) -> bool {
+        match self {
+            Encoding::Latin1 => string.chars().all(|char| u8::try_from(char).is_ok()),
+            _ => true,
+        }
+    }
+
+    pub fn encoding_byte(&self) -> u8 {
+        match self {
+            Encoding::Latin1 => 0,
+            Encoding::UTF16(_) => 1,
+            Encoding::UTF16BE => 2,
+            Encoding::UTF8 => 3,
+        }
+    }
+
+    /// Reads a string up to its terminator, returning it along with the bytes that follow the
+    /// terminator. A missing terminator means the string spans the rest of the bytes. UTF-16
+    /// strings may start with their own BOM.
+    pub fn read_string<'a>(&self, bytes: &'a [u8]) -> (String, &'a [u8]) {
+        let separator = self.string_separator();
+        // UTF-16 terminators are only looked for on code unit boundaries, as the second byte of
+        // a code unit followed by the first byte of the next one may both be zero
+        let end = bytes
+            .chunks(separator.len())
+            .position(|chunk| chunk == separator)
+            .map(|position| position * separator.len());
+
+        match end {
+            Some(end) => (
+                self.make_string(&bytes[..end]),
+                &bytes[end + separator.len()..],
+            ),
+            None => (self.make_string(bytes), &bytes[bytes.len()..]),
+        }
+    }
+
+    /// Encodes a string without its terminator, failing instead of replacing the characters
+    /// the encoding lacks.
+    pub fn try_encode_string(&self, string: &str) -> Result<Vec<u8>, EncodingError> {
+        if let Some(char) = string
+            .chars()
+            .find(|char| !self.can_encode(&char.to_string()))
+        {
+            return Err(EncodingError::UnrepresentableCharacter(char));
+        }
+        Ok(self.encode_string(string))
+    }
+
+    /// Encodes a string without its terminator. UTF-16 strings are prefixed with a BOM.
+    /// Characters Latin-1 lacks are replaced with a question mark.
+    pub fn encode_string(&self, string: &str) -> Vec<u8> {
+        match self {
+            Encoding::Latin1 => string_to_latin1(string),
+            Encoding::UTF16(byte_order) => {
+                let mut bytes = match byte_order {
+                    ByteOrder::BigEndian => vec![0xFE, 0xFF],
+                    ByteOrder::LittleEndian => vec![0xFF, 0xFE],
+                };
+                bytes.extend(encode_utf16(string, byte_order));
+                bytes
+            }
+            Encoding::UTF16BE => encode_utf16(string, &ByteOrder::BigEndian),
+            Encoding::UTF8 => string.as_bytes().to_vec(),
+        }
+    }
+
     pub fn string_separator(&self) -> &[u8] {
         match self {
             Encoding::Latin1 => &[0],
@@ -60,53 +171,317 @@ impl Encoding {
         }
     }
 
+    /// Splits the bytes into strings, without their terminators. A terminator at the very end
+    /// doesn't start another string.
     pub fn split_bytes_by_string_separator<'a>(&self, bytes: &'a [u8]) -> Vec<&'a [u8]> {
-        let mut bytes = bytes;
         let separator = self.string_separator();
-        let mut vector = vec![];
-        while let Some(index) = find_substring(bytes, separator) {
-            vector.push(&bytes[..index]);
-            bytes = &bytes[index + separator.len()..];
+        let mut strings = vec![];
+        let mut start = 0;
+        for (index, chunk) in bytes.chunks(separator.len()).enumerate() {
+            if chunk == separator {
+                strings.push(&bytes[start..index * separator.len()]);
+                start = (index + 1) * separator.len();
+            }
         }
-        vector
+        if start < bytes.len() {
+            strings.push(&bytes[start..]);
+        }
+        strings
     }
 
+    /// Decodes a single string, which must not include its terminator. Invalid sequences, as
+    /// unpaired surrogates or the last byte of an odd length UTF-16 string, are replaced with
+    /// U+FFFD.
     pub fn make_string(&self, bytes: &[u8]) -> String {
-        match self {
-            Encoding::Latin1 => latin1_to_string(bytes),
-            Encoding::UTF16(byte_order) => String::from_utf16_lossy(
-                &bytes
-                    .windows(2)
-                    .map(|bytes| {
-                        let b1 = bytes[0];
-                        let b2 = bytes[1];
-                        match byte_order {
-                            ByteOrder::BigEndian => ((b1 as u16) << 8) | (b2 as u16),
-                            ByteOrder::LittleEndian => ((b2 as u16) << 8) | (b1 as u16),
-                        }
-                    })
-                    .collect::<Vec<u16>>()[..],
-            ),
-            Encoding::UTF16BE => String::from_utf16_lossy(
-                &bytes
-                    .windows(2)
-                    .map(|bytes| {
-                        let b1 = bytes[0];
-                        let b2 = bytes[1];
-                        ((b1 as u16) << 8) | (b2 as u16)
-                    })
-                    .collect::<Vec<u16>>()[..],
-            ),
-            Encoding::UTF8 => String::from_utf8_lossy(bytes).to_string(),
+        match (self, bytes) {
+            (Encoding::Latin1, bytes) => latin1_to_string(bytes),
+            (Encoding::UTF16(_), [0xFE, 0xFF, bytes @ ..]) => {
+                decode_utf16(bytes, &ByteOrder::BigEndian)
+            }
+            (Encoding::UTF16(_), [0xFF, 0xFE, bytes @ ..]) => {
+                decode_utf16(bytes, &ByteOrder::LittleEndian)
+            }
+            (Encoding::UTF16(byte_order), bytes) => decode_utf16(bytes, byte_order),
+            (Encoding::UTF16BE, bytes) => decode_utf16(bytes, &ByteOrder::BigEndian),
+            // Some taggers write a BOM even though UTF-8 doesn't need one
+            (Encoding::UTF8, [0xEF, 0xBB, 0xBF, bytes @ ..]) | (Encoding::UTF8, bytes) => {
+                String::from_utf8_lossy(bytes).to_string()
+            }
         }
     }
 
+    /// in bytes, of a code unit and of the string terminator
     pub fn bytes_length(&self) -> usize {
         match self {
             Encoding::Latin1 => 1,
             Encoding::UTF16(_) => 2,
-            Encoding::UTF16BE => 1,
+            Encoding::UTF16BE => 2,
             Encoding::UTF8 => 1,
         }
     }
 }
+
+fn decode_utf16(bytes: &[u8], byte_order: &ByteOrder) -> String {
+    let pairs = bytes.chunks_exact(2);
+    let odd_byte = !pairs.remainder().is_empty();
+    let units = pairs
+        .map(|pair| match byte_order {
+            ByteOrder::BigEndian => u16::from_be_bytes([pair[0], pair[1]]),
+            ByteOrder::LittleEndian => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect::<Vec<u16>>();
+    let mut string = String::from_utf16_lossy(&units);
+    if odd_byte {
+        string.push(char::REPLACEMENT_CHARACTER);
+    }
+    string
+}
+
+fn encode_utf16(string: &str, byte_order: &ByteOrder) -> Vec<u8> {
+    string
+        .encode_utf16()
+        .flat_map(|unit| match byte_order {
+            ByteOrder::BigEndian => unit.to_be_bytes(),
+            ByteOrder::LittleEndian => unit.to_le_bytes(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const LE: Encoding = Encoding::UTF16(ByteOrder::LittleEndian);
+    const BE: Encoding = Encoding::UTF16(ByteOrder::BigEndian);
+
+    /// G clef, outside of the basic multilingual plane
+    const CLEF: &str = "\u{1D11E}";
+
+    #[test]
+    fn encoding_bytes() {
+        assert_eq!(Encoding::extract_from_vec(&[0]), Ok(Encoding::Latin1));
+        assert_eq!(Encoding::extract_from_vec(&[1, 0xFE, 0xFF]), Ok(BE));
+        assert_eq!(Encoding::extract_from_vec(&[1, 0xFF, 0xFE]), Ok(LE));
+        assert_eq!(
+            Encoding::extract_from_vec(&[1, 0xFF]),
+            Err(EncodingError::MissingBOM)
+        );
+        assert_eq!(
+            Encoding::extract_from_vec(&[1, b'a', 0]),
+            Err(EncodingError::InvalidBOM(b'a', 0))
+        );
+        assert_eq!(Encoding::extract_from_vec(&[2]), Ok(Encoding::UTF16BE));
+        assert_eq!(Encoding::extract_from_vec(&[3]), Ok(Encoding::UTF8));
+        assert_eq!(
+            Encoding::extract_from_vec(&[4]),
+            Err(EncodingError::UnknownEncoding(4))
+        );
+        assert_eq!(
+            Encoding::extract_from_vec(&[]),
+            Err(EncodingError::MissingEncoding)
+        );
+
+        for encoding in [Encoding::Latin1, LE, Encoding::UTF16BE, Encoding::UTF8] {
+            assert_eq!(
+                Encoding::from_encoding_byte(encoding.encoding_byte()),
+                Ok(encoding)
+            );
+        }
+    }
+
+    #[test]
+    fn bytes_length() {
+        assert_eq!(Encoding::Latin1.bytes_length(), 1);
+        assert_eq!(LE.bytes_length(), 2);
+        assert_eq!(BE.bytes_length(), 2);
+        assert_eq!(Encoding::UTF16BE.bytes_length(), 2);
+        assert_eq!(Encoding::UTF8.bytes_length(), 1);
+        for encoding in [Encoding::Latin1, LE, BE, Encoding::UTF16BE, Encoding::UTF8] {
+            assert_eq!(encoding.string_separator().len(), encoding.bytes_length());
+        }
+    }
+
+    #[test]
+    fn latin1() {
+        assert_eq!(Encoding::Latin1.make_string(b"Caf\xE9"), "Café");
+        assert_eq!(Encoding::Latin1.encode_string("Café"), b"Caf\xE9");
+        // Characters outside of ISO-8859-1 must not become terminators
+        assert_eq!(Encoding::Latin1.encode_string("Ā€"), b"??");
+    }
+
+    #[test]
+    fn utf8() {
+        assert_eq!(
+            Encoding::UTF8.make_string("Ünïcödé ♫".as_bytes()),
+            "Ünïcödé ♫"
+        );
+        assert_eq!(Encoding::UTF8.make_string(b"\xEF\xBB\xBFTitle"), "Title");
+        assert_eq!(Encoding::UTF8.make_string(b"a\xFFb"), "a\u{FFFD}b");
+        assert_eq!(Encoding::UTF8.encode_string("♫"), "♫".as_bytes());
+    }
+
+    #[test]
+    fn utf16_with_bom() {
+        assert_eq!(LE.make_string(b"\xFF\xFEa\x00b\x00"), "ab");
+        assert_eq!(LE.make_string(b"\xFE\xFF\x00a\x00b"), "ab");
+        assert_eq!(BE.make_string(b"\xFF\xFEa\x00b\x00"), "ab");
+        // Without a BOM, the byte order of the encoding applies
+        assert_eq!(LE.make_string(b"a\x00b\x00"), "ab");
+        assert_eq!(BE.make_string(b"\x00a\x00b"), "ab");
+
+        assert_eq!(LE.encode_string("ab"), b"\xFF\xFEa\x00b\x00");
+        assert_eq!(BE.encode_string("ab"), b"\xFE\xFF\x00a\x00b");
+        assert_eq!(LE.encode_string(""), b"\xFF\xFE");
+    }
+
+    #[test]
+    fn utf16_big_endian() {
+        assert_eq!(Encoding::UTF16BE.make_string(b"\x00a\x01\x00"), "aĀ");
+        assert_eq!(Encoding::UTF16BE.encode_string("aĀ"), b"\x00a\x01\x00");
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        assert_eq!(LE.encode_string(CLEF), b"\xFF\xFE\x34\xD8\x1E\xDD");
+        assert_eq!(Encoding::UTF16BE.encode_string(CLEF), b"\xD8\x34\xDD\x1E");
+        assert_eq!(LE.make_string(b"\xFF\xFE\x34\xD8\x1E\xDD"), CLEF);
+        assert_eq!(Encoding::UTF16BE.make_string(b"\xD8\x34\xDD\x1E"), CLEF);
+
+        // Unpaired surrogates
+        assert_eq!(Encoding::UTF16BE.make_string(b"\xD8\x34\x00a"), "\u{FFFD}a");
+        assert_eq!(Encoding::UTF16BE.make_string(b"\xDD\x1E"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn odd_length() {
+        assert_eq!(LE.make_string(b"\xFF\xFEa\x00b"), "a\u{FFFD}");
+        assert_eq!(Encoding::UTF16BE.make_string(b"\x00"), "\u{FFFD}");
+
+        let (string, rest) = LE.read_string(b"\xFF\xFEa\x00b");
+        assert_eq!(string, "a\u{FFFD}");
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn terminators() {
+        assert_eq!(
+            Encoding::Latin1.read_string(b"one\x00two"),
+            (String::from("one"), b"two".as_slice())
+        );
+        assert_eq!(
+            Encoding::UTF8.read_string(b"one"),
+            (String::from("one"), b"".as_slice())
+        );
+        assert_eq!(
+            Encoding::UTF8.read_string(b"\x00two"),
+            (String::from(""), b"two".as_slice())
+        );
+        // "aĀ" in little endian holds two zero bytes across code units, which isn't a terminator
+        assert_eq!(
+            LE.read_string(b"a\x00\x00\x01\x00\x00rest"),
+            (String::from("aĀ"), b"rest".as_slice())
+        );
+        assert_eq!(
+            Encoding::UTF16BE.read_string(b"\x00a\x00\x00"),
+            (String::from("a"), b"".as_slice())
+        );
+    }
+
+    #[test]
+    fn bom_per_string() {
+        let bytes = b"\xFF\xFEa\x00\x00\x00\xFE\xFF\x00b\x00\x00c\x00";
+        let (first, rest) = LE.read_string(bytes);
+        let (second, rest) = LE.read_string(rest);
+        let (third, rest) = LE.read_string(rest);
+        assert_eq!([first, second, third], ["a", "b", "c"]);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn split_strings() {
+        assert_eq!(
+            Encoding::Latin1.split_bytes_by_string_separator(b"one\x00two\x00"),
+            [b"one".as_slice(), b"two"]
+        );
+        assert_eq!(
+            Encoding::Latin1.split_bytes_by_string_separator(b"one\x00\x00three"),
+            [b"one".as_slice(), b"", b"three"]
+        );
+        assert_eq!(
+            LE.split_bytes_by_string_separator(b"a\x00\x00\x01\x00\x00b\x00"),
+            [b"a\x00\x00\x01".as_slice(), b"b\x00"]
+        );
+        assert!(Encoding::UTF8
+            .split_bytes_by_string_separator(b"")
+            .is_empty());
+    }
+
+    #[test]
+    fn selection() {
+        let restricted = Some(&TextEncodingRestrictions::ISO88591OrUTF8);
+        assert_eq!(
+            Encoding::select(3, None, &["Title", "Café"]),
+            Ok(Encoding::Latin1)
+        );
+        assert_eq!(
+            Encoding::select(4, restricted, &["Café"]),
+            Ok(Encoding::Latin1)
+        );
+        assert_eq!(
+            Encoding::select(4, None, &["Café", "東京"]),
+            Ok(Encoding::UTF8)
+        );
+        assert_eq!(
+            Encoding::select(4, restricted, &["Москва"]),
+            Ok(Encoding::UTF8)
+        );
+        assert_eq!(Encoding::select(3, None, &["東京"]), Ok(LE));
+        assert_eq!(
+            Encoding::select(
+                3,
+                Some(&TextEncodingRestrictions::NoRestrictions),
+                &["東京"]
+            ),
+            Ok(LE)
+        );
+        assert_eq!(
+            Encoding::select(3, restricted, &["東京"]),
+            Err(EncodingError::ForbiddenEncoding(3))
+        );
+        assert_eq!(Encoding::select(4, None, &[]), Ok(Encoding::Latin1));
+    }
+
+    #[test]
+    fn unrepresentable_characters() {
+        assert!(Encoding::Latin1.can_encode("ÿ"));
+        assert!(!Encoding::Latin1.can_encode("Ā"));
+        assert!(Encoding::UTF16BE.can_encode("Ā"));
+        assert_eq!(
+            Encoding::Latin1.try_encode_string("Москва"),
+            Err(EncodingError::UnrepresentableCharacter('М'))
+        );
+        assert_eq!(Encoding::Latin1.try_encode_string("ÿ"), Ok(vec![0xFF]));
+        assert_eq!(
+            Encoding::UTF8.try_encode_string("Москва"),
+            Ok("Москва".as_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let strings = ["", "Title", "Café", "Ωμέγα", "日本語", CLEF, "a\u{FEFF}b"];
+        for encoding in [LE, BE, Encoding::UTF16BE, Encoding::UTF8] {
+            for string in strings {
+                let mut bytes = encoding.encode_string(string);
+                bytes.extend_from_slice(encoding.string_separator());
+                bytes.extend_from_slice(b"after");
+                assert_eq!(
+                    encoding.read_string(&bytes),
+                    (String::from(string), b"after".as_slice()),
+                    "{string} in {encoding:?}"
+                );
+            }
+        }
+    }
+}
@@ -2,7 +2,10 @@ use std::io::Read;
 
 use thiserror::Error;
 
-use crate::utils::{read_syncsafe_integer, BitPosition};
+use crate::utils::{
+    read_syncsafe_integer, read_syncsafe_integer_5bytes, write_syncsafe_integer,
+    write_syncsafe_integer_5bytes, BitPosition,
+};
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct ExtendedHeader {
@@ -10,6 +13,8 @@ pub struct ExtendedHeader {
     number_of_flag_bytes: u8,
     flag_bytes: Vec<u8>,
     flag_data: Vec<ExtendedHeaderFlagData>,
+    /// in bytes, only stored by ID3v2.3
+    padding_size: u32,
 }
 
 #[derive(PartialEq, Clone, Debug, Error)]
@@ -23,14 +28,175 @@ pub enum ExtendedHeaderReadError {
 }
 
 impl ExtendedHeader {
-    pub fn bytes_size(&self) -> u32 {
+    /// An ID3v2.4 extended header. The CRC is the one of the frames and padding, see
+    /// [`ExtendedHeader::with_crc`].
+    pub fn new(
+        is_update: bool,
+        crc: Option<u32>,
+        restrictions: Option<TagRestrictions>,
+    ) -> ExtendedHeader {
+        let mut flag_data = vec![];
+        if is_update {
+            flag_data.push(ExtendedHeaderFlagData {
+                size: 0,
+                data: vec![],
+                typ: ExtendedHeaderFlagDataType::TagIsAnUpdate,
+            });
+        }
+        if let Some(crc) = crc {
+            flag_data.push(ExtendedHeaderFlagData {
+                size: 5,
+                data: write_syncsafe_integer_5bytes(crc as u64).to_vec(),
+                typ: ExtendedHeaderFlagDataType::CrcDataPresent,
+            });
+        }
+        if let Some(restrictions) = restrictions {
+            flag_data.push(ExtendedHeaderFlagData {
+                size: 1,
+                data: vec![restrictions.binary_representation()],
+                typ: ExtendedHeaderFlagDataType::TagRestrictions(restrictions),
+            });
+        }
+        let flags_byte = flag_data
+            .iter()
+            .map(|flag| match flag.typ {
+                ExtendedHeaderFlagDataType::TagIsAnUpdate => 0b01000000,
+                ExtendedHeaderFlagDataType::CrcDataPresent => 0b00100000,
+                ExtendedHeaderFlagDataType::TagRestrictions(_) => 0b00010000,
+                ExtendedHeaderFlagDataType::Unknown(..) => 0,
+            })
+            .fold(0, |byte, bit| byte | bit);
+
+        let mut extended_header = ExtendedHeader {
+            extended_header_size: 0,
+            number_of_flag_bytes: 1,
+            flag_bytes: vec![flags_byte],
+            flag_data,
+            padding_size: 0,
+        };
+        extended_header.extended_header_size = extended_header.bytes_size(4);
+        extended_header
+    }
+
+    /// An ID3v2.3 extended header, which can only hold a CRC. The CRC is the one of the frames,
+    /// without the padding, see [`ExtendedHeader::with_crc`].
+    pub fn new_v23(crc: Option<u32>) -> ExtendedHeader {
+        let flag_data = crc
+            .map(|crc| ExtendedHeaderFlagData {
+                size: 4,
+                data: crc.to_be_bytes().to_vec(),
+                typ: ExtendedHeaderFlagDataType::CrcDataPresent,
+            })
+            .into_iter()
+            .collect::<Vec<_>>();
+        let flags_byte = if crc.is_some() { 0b10000000 } else { 0 };
+
+        let mut extended_header = ExtendedHeader {
+            extended_header_size: 0,
+            number_of_flag_bytes: 2,
+            flag_bytes: vec![flags_byte, 0],
+            flag_data,
+            padding_size: 0,
+        };
+        // The size of an ID3v2.3 extended header excludes its own 4 bytes
+        extended_header.extended_header_size = extended_header.bytes_size(3) - 4;
+        extended_header
+    }
+
+    /// Whether this tag updates a tag found earlier in the file or stream
+    pub fn is_update(&self) -> bool {
+        self.flag_data
+            .iter()
+            .any(|flag| flag.typ == ExtendedHeaderFlagDataType::TagIsAnUpdate)
+    }
+
+    /// The CRC-32 of the frames and padding, if present
+    pub fn crc(&self) -> Option<u32> {
+        self.flag_data
+            .iter()
+            .find(|flag| flag.typ == ExtendedHeaderFlagDataType::CrcDataPresent)
+            .and_then(|flag| match *flag.data.as_slice() {
+                // ID3v2.3 doesn't make it syncsafe
+                [b1, b2, b3, b4] => Some(u32::from_be_bytes([b1, b2, b3, b4])),
+                [b1, b2, b3, b4, b5] => {
+                    Some(read_syncsafe_integer_5bytes([b1, b2, b3, b4, b5]) as u32)
+                }
+                _ => None,
+            })
+    }
+
+    /// The same extended header, with the CRC data replaced, if present.
+    pub fn with_crc(&self, crc: u32) -> ExtendedHeader {
+        let mut extended_header = self.clone();
+        for flag in &mut extended_header.flag_data {
+            if flag.typ == ExtendedHeaderFlagDataType::CrcDataPresent {
+                flag.data = if flag.data.len() == 4 {
+                    crc.to_be_bytes().to_vec()
+                } else {
+                    write_syncsafe_integer_5bytes(crc as u64).to_vec()
+                };
+            }
+        }
+        extended_header
+    }
+
+    /// The same extended header, with the padding size replaced. Only ID3v2.3 stores it.
+    pub fn with_padding_size(&self, padding_size: u32) -> ExtendedHeader {
+        ExtendedHeader {
+            padding_size,
+            ..self.clone()
+        }
+    }
+
+    pub fn restrictions(&self) -> Option<&TagRestrictions> {
+        self.flag_data.iter().find_map(|flag| match &flag.typ {
+            ExtendedHeaderFlagDataType::TagRestrictions(restrictions) => Some(restrictions),
+            _ => None,
+        })
+    }
+
+    pub fn bytes_size(&self, major_version: u8) -> u32 {
+        if major_version == 3 {
+            // Size, flags and padding size, followed by the CRC
+            let flags_bytes: u32 = self.flag_data.iter().map(|flag| flag.size as u32).sum();
+            return 10 + flags_bytes;
+        }
+
         let minimum_bytes = 5u32;
         let flags_bytes: u32 = { self.flag_data.iter().map(|flag| flag.size as u32 + 1).sum() };
 
         minimum_bytes + self.number_of_flag_bytes as u32 + flags_bytes
     }
 
-    pub fn read(reader: &mut impl Read) -> Result<ExtendedHeader, ExtendedHeaderReadError> {
+    pub fn to_bytes(&self, major_version: u8) -> Vec<u8> {
+        if major_version == 3 {
+            let mut bytes = (self.bytes_size(3) - 4).to_be_bytes().to_vec();
+            bytes.extend_from_slice(&self.flag_bytes);
+            bytes.extend_from_slice(&self.padding_size.to_be_bytes());
+            for flag in &self.flag_data {
+                bytes.extend_from_slice(&flag.data);
+            }
+            return bytes;
+        }
+
+        let mut bytes = write_syncsafe_integer(self.bytes_size(4)).to_vec();
+        bytes.push(self.number_of_flag_bytes);
+        bytes.extend_from_slice(&self.flag_bytes);
+        for flag in &self.flag_data {
+            bytes.push(flag.size);
+            bytes.extend_from_slice(&flag.data);
+        }
+        bytes
+    }
+
+    pub fn read(
+        reader: &mut impl Read,
+        major_version: u8,
+    ) -> Result<ExtendedHeader, ExtendedHeaderReadError> {
+        if major_version == 3 {
+            return Self::read_v23(reader);
+        }
+
         let mut buffer = [0u8; 5];
         reader
             .read_exact(&mut buffer)
@@ -39,9 +205,7 @@ impl ExtendedHeader {
             read_syncsafe_integer([buffer[0], buffer[1], buffer[2], buffer[3]]);
 
         let number_of_flag_bytes = buffer[4];
-        let mut flag_bytes = vec![];
-
-        flag_bytes.resize(number_of_flag_bytes as usize, 0);
+        let mut flag_bytes = vec![0; number_of_flag_bytes as usize];
 
         reader
             .read_exact(&mut flag_bytes[..])
@@ -54,21 +218,55 @@ impl ExtendedHeader {
             number_of_flag_bytes,
             flag_bytes,
             flag_data,
+            padding_size: 0,
+        })
+    }
+
+    /// The ID3v2.3 layout: a size that excludes its own 4 bytes, 2 flag bytes, the padding size,
+    /// and the CRC when the first flag is set.
+    fn read_v23(reader: &mut impl Read) -> Result<ExtendedHeader, ExtendedHeaderReadError> {
+        let mut buffer = [0u8; 10];
+        reader
+            .read_exact(&mut buffer)
+            .map_err(|_| ExtendedHeaderReadError::NotEnoughBytes)?;
+        let [s1, s2, s3, s4, flags_1, flags_2, p1, p2, p3, p4] = buffer;
+
+        let mut flag_data = vec![];
+        if flags_1 & 0b10000000 != 0 {
+            let mut crc = vec![0; 4];
+            reader
+                .read_exact(&mut crc)
+                .map_err(|_| ExtendedHeaderReadError::NotEnoughBytes)?;
+            flag_data.push(ExtendedHeaderFlagData {
+                size: 4,
+                data: crc,
+                typ: ExtendedHeaderFlagDataType::CrcDataPresent,
+            });
+        }
+
+        Ok(ExtendedHeader {
+            extended_header_size: u32::from_be_bytes([s1, s2, s3, s4]),
+            number_of_flag_bytes: 2,
+            flag_bytes: vec![flags_1, flags_2],
+            flag_data,
+            padding_size: u32::from_be_bytes([p1, p2, p3, p4]),
         })
     }
 
     fn parse_flags_data(
-        flags_bytes: &Vec<u8>,
+        flags_bytes: &[u8],
         reader: &mut impl Read,
     ) -> Result<Vec<ExtendedHeaderFlagData>, ExtendedHeaderReadError> {
         let mut flags_data: Vec<ExtendedHeaderFlagData> = vec![];
 
         let set_positions = flags_bytes
             .iter()
-            .flat_map(|flags_byte| {
+            .enumerate()
+            .flat_map(|(byte_position, flags_byte)| {
                 BitPosition::iter_right()
+                    .rev()
                     .filter(|pos| pos.is_set_on(*flags_byte))
-                    .map(|pos| (*flags_byte, pos))
+                    .map(move |pos| (byte_position as u8, pos))
             })
             .collect::<Vec<(u8, BitPosition)>>();
 
@@ -79,8 +277,7 @@ impl ExtendedHeader {
                 .map_err(|_| ExtendedHeaderReadError::NotEnoughBytes)?;
 
             let size = buffer[0];
-            let mut data = vec![];
-            data.resize(size as usize, 0);
+            let mut data = vec![0; size as usize];
 
             reader
                 .read_exact(&mut data)
@@ -89,57 +286,9 @@ impl ExtendedHeader {
             let typ = match (byte_position, &bit_position) {
                 (0, BitPosition::LSBPlus6) => ExtendedHeaderFlagDataType::TagIsAnUpdate,
                 (0, BitPosition::LSBPlus5) => ExtendedHeaderFlagDataType::CrcDataPresent,
-                (0, BitPosition::LSBPlus4) => {
-                    let data_byte = data.get(0).unwrap_or(&0);
-                    let tag_size_restrictions_byte = (data_byte & 0b11000000) >> 6;
-                    let text_encoding_restrictions_byte = (data_byte & 0b00100000) >> 5;
-                    let text_field_size_restrictions_byte = (data_byte & 0b00011000) >> 3;
-                    let image_encoding_restrictions_byte = (data_byte & 0b00000100) >> 2;
-                    let image_size_restrictions_byte = data_byte & 0b00000011;
-                    ExtendedHeaderFlagDataType::TagRestrictions(TagRestrictions {
-                        tag_size_restrictions: {
-                            match tag_size_restrictions_byte {
-                                0b00 => TagSizeRestrictions::Max128Frames1MB,
-                                0b01 => TagSizeRestrictions::Max64Frames128KB,
-                                0b10 => TagSizeRestrictions::Max32Frames40KB,
-                                0b11 => TagSizeRestrictions::Max32Frames4KB,
-                                _ => unreachable!(),
-                            }
-                        },
-                        text_encoding_restrictions: {
-                            match text_encoding_restrictions_byte {
-                                0b00 => TextEncodingRestrictions::NoRestrictions,
-                                0b01 => TextEncodingRestrictions::ISO88591OrUTF8,
-                                _ => unreachable!(),
-                            }
-                        },
-                        text_field_size_restrictions: {
-                            match text_field_size_restrictions_byte {
-                                0b00 => TextFieldSizeRestrictions::NoRestrictions,
-                                0b01 => TextFieldSizeRestrictions::Max1024Chars,
-                                0b10 => TextFieldSizeRestrictions::Max128Chars,
-                                0b11 => TextFieldSizeRestrictions::Max30Chars,
-                                _ => unreachable!(),
-                            }
-                        },
-                        image_encoding_restrictions: {
-                            match image_encoding_restrictions_byte {
-                                0b00 => ImageEncodingRestrictions::NoRestrictions,
-                                0b01 => ImageEncodingRestrictions::PngOrJpeg,
-                                _ => unreachable!(),
-                            }
-                        },
-                        image_size_restrictions: {
-                            match image_size_restrictions_byte {
-                                0b00 => ImageSizeRestrictions::NoRestrictions,
-                                0b01 => ImageSizeRestrictions::Max256x256Pixels,
-                                0b10 => ImageSizeRestrictions::Max64x64Pixels,
-                                0b11 => ImageSizeRestrictions::Exactly64x64Pixels,
-                                _ => unreachable!(),
-                            }
-                        },
-                    })
-                }
+                (0, BitPosition::LSBPlus4) => ExtendedHeaderFlagDataType::TagRestrictions(
+                    TagRestrictions::from(*data.first().unwrap_or(&0)),
+                ),
                 _ => ExtendedHeaderFlagDataType::Unknown(byte_position, bit_position),
             };
 
@@ -153,10 +302,11 @@ impl ExtendedHeader {
 impl Default for ExtendedHeader {
     fn default() -> Self {
         Self {
-            extended_header_size: 5,
-            number_of_flag_bytes: 0,
+            extended_header_size: 6,
+            number_of_flag_bytes: 1,
             flag_data: vec![],
             flag_bytes: vec![0],
+            padding_size: 0,
         }
     }
 }
@@ -170,6 +320,26 @@ pub enum TagSizeRestrictions {
     Max32Frames4KB,
 }
 
+impl TagSizeRestrictions {
+    pub fn max_frames(&self) -> usize {
+        match self {
+            Self::Max128Frames1MB => 128,
+            Self::Max64Frames128KB => 64,
+            Self::Max32Frames40KB | Self::Max32Frames4KB => 32,
+        }
+    }
+
+    /// in bytes, for the whole tag
+    pub fn max_size(&self) -> u32 {
+        match self {
+            Self::Max128Frames1MB => 1024 * 1024,
+            Self::Max64Frames128KB => 128 * 1024,
+            Self::Max32Frames40KB => 40 * 1024,
+            Self::Max32Frames4KB => 4 * 1024,
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Debug, Default)]
 pub enum TextEncodingRestrictions {
     #[default]
@@ -203,11 +373,100 @@ pub enum ImageSizeRestrictions {
 
 #[derive(PartialEq, Clone, Debug, Default)]
 pub struct TagRestrictions {
-    tag_size_restrictions: TagSizeRestrictions,
-    text_encoding_restrictions: TextEncodingRestrictions,
-    text_field_size_restrictions: TextFieldSizeRestrictions,
-    image_encoding_restrictions: ImageEncodingRestrictions,
-    image_size_restrictions: ImageSizeRestrictions,
+    pub tag_size_restrictions: TagSizeRestrictions,
+    pub text_encoding_restrictions: TextEncodingRestrictions,
+    pub text_field_size_restrictions: TextFieldSizeRestrictions,
+    pub image_encoding_restrictions: ImageEncodingRestrictions,
+    pub image_size_restrictions: ImageSizeRestrictions,
+}
+
+impl From<u8> for TagRestrictions {
+    fn from(data_byte: u8) -> Self {
+        let tag_size_restrictions_byte = (data_byte & 0b11000000) >> 6;
+        let text_encoding_restrictions_byte = (data_byte & 0b00100000) >> 5;
+        let text_field_size_restrictions_byte = (data_byte & 0b00011000) >> 3;
+        let image_encoding_restrictions_byte = (data_byte & 0b00000100) >> 2;
+        let image_size_restrictions_byte = data_byte & 0b00000011;
+        TagRestrictions {
+            tag_size_restrictions: {
+                match tag_size_restrictions_byte {
+                    0b00 => TagSizeRestrictions::Max128Frames1MB,
+                    0b01 => TagSizeRestrictions::Max64Frames128KB,
+                    0b10 => TagSizeRestrictions::Max32Frames40KB,
+                    0b11 => TagSizeRestrictions::Max32Frames4KB,
+                    _ => unreachable!(),
+                }
+            },
+            text_encoding_restrictions: {
+                match text_encoding_restrictions_byte {
+                    0b00 => TextEncodingRestrictions::NoRestrictions,
+                    0b01 => TextEncodingRestrictions::ISO88591OrUTF8,
+                    _ => unreachable!(),
+                }
+            },
+            text_field_size_restrictions: {
+                match text_field_size_restrictions_byte {
+                    0b00 => TextFieldSizeRestrictions::NoRestrictions,
+                    0b01 => TextFieldSizeRestrictions::Max1024Chars,
+                    0b10 => TextFieldSizeRestrictions::Max128Chars,
+                    0b11 => TextFieldSizeRestrictions::Max30Chars,
+                    _ => unreachable!(),
+                }
+            },
+            image_encoding_restrictions: {
+                match image_encoding_restrictions_byte {
+                    0b00 => ImageEncodingRestrictions::NoRestrictions,
+                    0b01 => ImageEncodingRestrictions::PngOrJpeg,
+                    _ => unreachable!(),
+                }
+            },
+            image_size_restrictions: {
+                match image_size_restrictions_byte {
+                    0b00 => ImageSizeRestrictions::NoRestrictions,
+                    0b01 => ImageSizeRestrictions::Max256x256Pixels,
+                    0b10 => ImageSizeRestrictions::Max64x64Pixels,
+                    0b11 => ImageSizeRestrictions::Exactly64x64Pixels,
+                    _ => unreachable!(),
+                }
+            },
+        }
+    }
+}
+
+impl TagRestrictions {
+    pub fn binary_representation(&self) -> u8 {
+        let tag_size_restrictions_byte = match self.tag_size_restrictions {
+            TagSizeRestrictions::Max128Frames1MB => 0b00,
+            TagSizeRestrictions::Max64Frames128KB => 0b01,
+            TagSizeRestrictions::Max32Frames40KB => 0b10,
+            TagSizeRestrictions::Max32Frames4KB => 0b11,
+        };
+        let text_encoding_restrictions_byte = match self.text_encoding_restrictions {
+            TextEncodingRestrictions::NoRestrictions => 0b0,
+            TextEncodingRestrictions::ISO88591OrUTF8 => 0b1,
+        };
+        let text_field_size_restrictions_byte = match self.text_field_size_restrictions {
+            TextFieldSizeRestrictions::NoRestrictions => 0b00,
+            TextFieldSizeRestrictions::Max1024Chars => 0b01,
+            TextFieldSizeRestrictions::Max128Chars => 0b10,
+            TextFieldSizeRestrictions::Max30Chars => 0b11,
+        };
+        let image_encoding_restrictions_byte = match self.image_encoding_restrictions {
+            ImageEncodingRestrictions::NoRestrictions => 0b0,
+            ImageEncodingRestrictions::PngOrJpeg => 0b1,
+        };
+        let image_size_restrictions_byte = match self.image_size_restrictions {
+            ImageSizeRestrictions::NoRestrictions => 0b00,
+            ImageSizeRestrictions::Max256x256Pixels => 0b01,
+            ImageSizeRestrictions::Max64x64Pixels => 0b10,
+            ImageSizeRestrictions::Exactly64x64Pixels => 0b11,
+        };
+        (tag_size_restrictions_byte << 6)
+            | (text_encoding_restrictions_byte << 5)
+            | (text_field_size_restrictions_byte << 3)
+            | (image_encoding_restrictions_byte << 2)
+            | image_size_restrictions_byte
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -225,3 +484,52 @@ pub struct ExtendedHeaderFlagData {
     data: Vec<u8>,
     typ: ExtendedHeaderFlagDataType,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_every_flag() {
+        // Update, CRC and restrictions, the first flags of the first flags byte
+        let bytes = b"\x00\x00\x00\x0F\x01\x70\x00\x05\x00\x00\x00\x02\x2A\x01\x20";
+        let extended_header = ExtendedHeader::read(&mut &bytes[..], 4).unwrap();
+
+        assert!(extended_header.is_update());
+        assert_eq!(extended_header.crc(), Some(0x012A));
+        assert_eq!(
+            extended_header.restrictions(),
+            Some(&TagRestrictions::from(0x20))
+        );
+        assert_eq!(extended_header.bytes_size(4), 15);
+        assert_eq!(extended_header.to_bytes(4), bytes);
+    }
+
+    #[test]
+    fn v23_crc() {
+        // A size excluding itself, the CRC flag, 256 bytes of padding and the CRC
+        let bytes = b"\x00\x00\x00\x0A\x80\x00\x00\x00\x01\x00\xDE\xAD\xBE\xEF";
+        let extended_header = ExtendedHeader::read(&mut &bytes[..], 3).unwrap();
+
+        assert_eq!(extended_header.crc(), Some(0xDEADBEEF));
+        assert!(!extended_header.is_update());
+        assert_eq!(extended_header.bytes_size(3), 14);
+        assert_eq!(extended_header.to_bytes(3), bytes);
+        assert_eq!(
+            ExtendedHeader::new_v23(Some(0xDEADBEEF))
+                .with_padding_size(256)
+                .to_bytes(3),
+            bytes
+        );
+
+        let bytes = b"\x00\x00\x00\x06\x00\x00\x00\x00\x00\x00";
+        let extended_header = ExtendedHeader::read(&mut &bytes[..], 3).unwrap();
+        assert_eq!(extended_header.crc(), None);
+        assert_eq!(extended_header, ExtendedHeader::new_v23(None));
+        assert_eq!(
+            ExtendedHeader::read(&mut &bytes[..8], 3),
+            Err(ExtendedHeaderReadError::NotEnoughBytes)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use thiserror::Error;
+
+use crate::{
+    tag::encoding::{Encoding, EncodingError},
+    utils::string_to_latin1,
+};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PictureType {
+    Other,
+    /// 32x32 pixels, PNG only
+    FileIcon,
+    OtherFileIcon,
+    FrontCover,
+    BackCover,
+    LeafletPage,
+    /// Label side of the CD
+    Media,
+    LeadArtist,
+    Artist,
+    Conductor,
+    Band,
+    Composer,
+    Lyricist,
+    RecordingLocation,
+    DuringRecording,
+    DuringPerformance,
+    /// Screen capture of a movie or video
+    MovieCapture,
+    ABrightColouredFish,
+    Illustration,
+    BandLogotype,
+    PublisherLogotype,
+    Reserved(u8),
+}
+
+impl From<u8> for PictureType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => PictureType::Other,
+            0x01 => PictureType::FileIcon,
+            0x02 => PictureType::OtherFileIcon,
+            0x03 => PictureType::FrontCover,
+            0x04 => PictureType::BackCover,
+            0x05 => PictureType::LeafletPage,
+            0x06 => PictureType::Media,
+            0x07 => PictureType::LeadArtist,
+            0x08 => PictureType::Artist,
+            0x09 => PictureType::Conductor,
+            0x0A => PictureType::Band,
+            0x0B => PictureType::Composer,
+            0x0C => PictureType::Lyricist,
+            0x0D => PictureType::RecordingLocation,
+            0x0E => PictureType::DuringRecording,
+            0x0F => PictureType::DuringPerformance,
+            0x10 => PictureType::MovieCapture,
+            0x11 => PictureType::ABrightColouredFish,
+            0x12 => PictureType::Illustration,
+            0x13 => PictureType::BandLogotype,
+            0x14 => PictureType::PublisherLogotype,
+            reserved => PictureType::Reserved(reserved),
+        }
+    }
+}
+
+impl PictureType {
+    pub fn binary_representation(&self) -> u8 {
+        match self {
+            PictureType::Other => 0x00,
+            PictureType::FileIcon => 0x01,
+            PictureType::OtherFileIcon => 0x02,
+            PictureType::FrontCover => 0x03,
+            PictureType::BackCover => 0x04,
+            PictureType::LeafletPage => 0x05,
+            PictureType::Media => 0x06,
+            PictureType::LeadArtist => 0x07,
+            PictureType::Artist => 0x08,
+            PictureType::Conductor => 0x09,
+            PictureType::Band => 0x0A,
+            PictureType::Composer => 0x0B,
+            PictureType::Lyricist => 0x0C,
+            PictureType::RecordingLocation => 0x0D,
+            PictureType::DuringRecording => 0x0E,
+            PictureType::DuringPerformance => 0x0F,
+            PictureType::MovieCapture => 0x10,
+            PictureType::ABrightColouredFish => 0x11,
+            PictureType::Illustration => 0x12,
+            PictureType::BandLogotype => 0x13,
+            PictureType::PublisherLogotype => 0x14,
+            PictureType::Reserved(reserved) => *reserved,
+        }
+    }
+}
+
+/// Content of an APIC frame.
+#[derive(PartialEq, Clone, Debug)]
+pub struct AttachedPicture {
+    /// Encoding of the description
+    pub encoding: Encoding,
+    /// "-->" when the data is a link to the picture
+    pub mime_type: String,
+    pub picture_type: PictureType,
+    pub description: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum AttachedPictureError {
+    #[error("Could not read enough bytes to parse the MIME type and picture type")]
+    MissingData,
+    #[error("Encoding Error: {0}")]
+    EncodingError(EncodingError),
+}
+
+impl AttachedPicture {
+    pub fn parse(bytes: &[u8]) -> Result<Self, AttachedPictureError> {
+        let Some((encoding_byte, bytes)) = bytes.split_first() else {
+            return Err(AttachedPictureError::MissingData);
+        };
+        let encoding = Encoding::from_encoding_byte(*encoding_byte)
+            .map_err(AttachedPictureError::EncodingError)?;
+        if !bytes.contains(&0) {
+            return Err(AttachedPictureError::MissingData);
+        }
+        let (mime_type, bytes) = Encoding::Latin1.read_string(bytes);
+        let Some((picture_type, bytes)) = bytes.split_first() else {
+            return Err(AttachedPictureError::MissingData);
+        };
+        let (description, data) = encoding.read_string(bytes);
+
+        Ok(AttachedPicture {
+            encoding,
+            mime_type,
+            picture_type: PictureType::from(*picture_type),
+            description,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.encoding.encoding_byte()];
+        bytes.extend(string_to_latin1(&self.mime_type));
+        bytes.push(0);
+        bytes.push(self.picture_type.binary_representation());
+        bytes.extend(self.encoding.encode_string(&self.description));
+        bytes.extend_from_slice(self.encoding.string_separator());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn front_cover() {
+        let bytes = b"\x00image/png\x00\x03Cover\x00\x89PNG\x00\x00".to_vec();
+        let apic = AttachedPicture::parse(&bytes);
+        assert_eq!(
+            apic,
+            Ok(AttachedPicture {
+                encoding: Encoding::Latin1,
+                mime_type: String::from("image/png"),
+                picture_type: PictureType::FrontCover,
+                description: String::from("Cover"),
+                data: b"\x89PNG\x00\x00".to_vec(),
+            })
+        );
+        assert_eq!(apic.unwrap().to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,82 @@
+use thiserror::Error;
+
+use crate::{tag::encoding::Encoding, utils::string_to_latin1};
+
+/// Content of an AENC frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AudioEncryption {
+    /// URL or email address of the organisation responsible for the encryption
+    pub owner_identifier: String,
+    /// in frames, where the unencrypted preview starts
+    pub preview_start: u16,
+    /// in frames, 0 if there's no unencrypted part
+    pub preview_length: u16,
+    pub encryption_info: Vec<u8>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum AudioEncryptionError {
+    #[error("Could not read enough bytes to parse the owner identifier and preview range")]
+    MissingData,
+}
+
+impl AudioEncryption {
+    pub fn parse(bytes: &[u8]) -> Result<Self, AudioEncryptionError> {
+        if !bytes.contains(&0) {
+            return Err(AudioEncryptionError::MissingData);
+        }
+        let (owner_identifier, bytes) = Encoding::Latin1.read_string(bytes);
+        let [s1, s2, l1, l2, encryption_info @ ..] = bytes else {
+            return Err(AudioEncryptionError::MissingData);
+        };
+
+        Ok(AudioEncryption {
+            owner_identifier,
+            preview_start: u16::from_be_bytes([*s1, *s2]),
+            preview_length: u16::from_be_bytes([*l1, *l2]),
+            encryption_info: encryption_info.to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = string_to_latin1(&self.owner_identifier);
+        bytes.push(0);
+        bytes.extend_from_slice(&self.preview_start.to_be_bytes());
+        bytes.extend_from_slice(&self.preview_length.to_be_bytes());
+        bytes.extend_from_slice(&self.encryption_info);
+        bytes
+    }
+
+    /// The frames that can be played without decrypting, if any.
+    pub fn preview_frames(&self) -> Option<std::ops::Range<u32>> {
+        if self.preview_length == 0 {
+            return None;
+        }
+        let start = self.preview_start as u32;
+        Some(start..start + self.preview_length as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_audio_encryption() {
+        let bytes = b"http://drm.example\x00\x00\x10\x01\x00info".to_vec();
+        let aenc = AudioEncryption::parse(&bytes);
+        assert_eq!(
+            aenc,
+            Ok(AudioEncryption {
+                owner_identifier: String::from("http://drm.example"),
+                preview_start: 16,
+                preview_length: 256,
+                encryption_info: b"info".to_vec(),
+            })
+        );
+        let aenc = aenc.unwrap();
+        assert_eq!(aenc.preview_frames(), Some(16..272));
+        assert_eq!(aenc.to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,204 @@
+use thiserror::Error;
+
+use crate::mpeg::MpegFrame;
+
+/// Content of an ASPI frame. The audio is divided into equal time segments, and each index point
+/// holds the position where its segment starts, as a fraction of the indexed data length.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AudioSeekPointIndex {
+    /// in bytes, from the beginning of the file
+    pub indexed_data_start: u32,
+    /// in bytes
+    pub indexed_data_length: u32,
+    /// Either 8 or 16
+    pub bits_per_index_point: u8,
+    /// Numerators of the fractions, whose denominator is 2 to the power of `bits_per_index_point`
+    pub fractions: Vec<u16>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum AudioSeekPointIndexError {
+    #[error("Could not read enough bytes to parse the index header")]
+    MissingData,
+    #[error("Index points of {0} bits are not supported. It should either be 8 or 16")]
+    UnsupportedBitsPerIndexPoint(u8),
+    #[error("The index should have {0} points, but it has {1}")]
+    WrongNumberOfIndexPoints(u16, usize),
+    #[error("At most 65535 index points can be stored, but there are {0}")]
+    TooManyIndexPoints(usize),
+    #[error("The fraction {0} does not fit in 8 bits")]
+    FractionTooLarge(u16),
+    #[error("At least 1 MPEG frame is needed to generate an index")]
+    NotEnoughFrames,
+    #[error("The indexed data is {0} bytes long, which does not fit in 32 bits")]
+    IndexedDataTooLong(u64),
+}
+
+impl AudioSeekPointIndex {
+    pub fn parse(bytes: &[u8]) -> Result<Self, AudioSeekPointIndexError> {
+        let [s1, s2, s3, s4, l1, l2, l3, l4, n1, n2, bits_per_index_point, fractions @ ..] = bytes
+        else {
+            return Err(AudioSeekPointIndexError::MissingData);
+        };
+        let number_of_index_points = u16::from_be_bytes([*n1, *n2]);
+
+        let fractions = match bits_per_index_point {
+            8 => fractions
+                .iter()
+                .map(|byte| *byte as u16)
+                .collect::<Vec<u16>>(),
+            16 => fractions
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect(),
+            _ => {
+                return Err(AudioSeekPointIndexError::UnsupportedBitsPerIndexPoint(
+                    *bits_per_index_point,
+                ))
+            }
+        };
+        if fractions.len() != number_of_index_points as usize {
+            return Err(AudioSeekPointIndexError::WrongNumberOfIndexPoints(
+                number_of_index_points,
+                fractions.len(),
+            ));
+        }
+
+        Ok(AudioSeekPointIndex {
+            indexed_data_start: u32::from_be_bytes([*s1, *s2, *s3, *s4]),
+            indexed_data_length: u32::from_be_bytes([*l1, *l2, *l3, *l4]),
+            bits_per_index_point: *bits_per_index_point,
+            fractions,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AudioSeekPointIndexError> {
+        let number_of_index_points = u16::try_from(self.fractions.len())
+            .map_err(|_| AudioSeekPointIndexError::TooManyIndexPoints(self.fractions.len()))?;
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&self.indexed_data_start.to_be_bytes());
+        bytes.extend_from_slice(&self.indexed_data_length.to_be_bytes());
+        bytes.extend_from_slice(&number_of_index_points.to_be_bytes());
+        bytes.push(self.bits_per_index_point);
+        for fraction in &self.fractions {
+            match self.bits_per_index_point {
+                8 => bytes.push(
+                    u8::try_from(*fraction)
+                        .map_err(|_| AudioSeekPointIndexError::FractionTooLarge(*fraction))?,
+                ),
+                16 => bytes.extend_from_slice(&fraction.to_be_bytes()),
+                bits => return Err(AudioSeekPointIndexError::UnsupportedBitsPerIndexPoint(bits)),
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// in bytes from the beginning of the file, where the segment of the given index point starts
+    pub fn byte_offset(&self, index_point: usize) -> Option<u64> {
+        let fraction = *self.fractions.get(index_point)? as u64;
+        let offset = (fraction * self.indexed_data_length as u64) >> self.bits_per_index_point;
+        Some(self.indexed_data_start as u64 + offset)
+    }
+
+    /// Builds an index with the given number of points from a scan of the audio frames. See
+    /// [`crate::mpeg::scan_frames`]. `indexed_data_start` is where the scan started in the file,
+    /// usually right after the tag.
+    pub fn generate(
+        frames: &[MpegFrame],
+        indexed_data_start: u32,
+        number_of_index_points: u16,
+        bits_per_index_point: u8,
+    ) -> Result<Self, AudioSeekPointIndexError> {
+        if bits_per_index_point != 8 && bits_per_index_point != 16 {
+            return Err(AudioSeekPointIndexError::UnsupportedBitsPerIndexPoint(
+                bits_per_index_point,
+            ));
+        }
+        let (Some(first), Some(last)) = (frames.first(), frames.last()) else {
+            return Err(AudioSeekPointIndexError::NotEnoughFrames);
+        };
+
+        let indexed_data_length = last.offset + last.header.frame_length() as u64 - first.offset;
+        let indexed_data_length = u32::try_from(indexed_data_length)
+            .map_err(|_| AudioSeekPointIndexError::IndexedDataTooLong(indexed_data_length))?;
+        let total_duration: f64 = frames.iter().map(MpegFrame::duration).sum();
+
+        let mut fractions = Vec::with_capacity(number_of_index_points as usize);
+        let mut frames_iter = frames.iter().peekable();
+        let mut elapsed = 0f64;
+        for index in 0..number_of_index_points {
+            let segment_start = total_duration * index as f64 / number_of_index_points as f64;
+            // Advance to the frame playing at the start of the segment
+            while let Some(frame) = frames_iter.peek() {
+                if elapsed + frame.duration() > segment_start {
+                    break;
+                }
+                elapsed += frame.duration();
+                frames_iter.next();
+            }
+            let offset = frames_iter.peek().unwrap_or(&last).offset - first.offset;
+            // Rounding up makes `byte_offset` land on the frame instead of just before it
+            let fraction = (offset << bits_per_index_point).div_ceil(indexed_data_length as u64);
+            let max_fraction = (1u64 << bits_per_index_point) - 1;
+            fractions.push(fraction.min(max_fraction) as u16);
+        }
+
+        Ok(AudioSeekPointIndex {
+            indexed_data_start,
+            indexed_data_length,
+            bits_per_index_point,
+            fractions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpeg::MpegFrameHeader;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_index() {
+        let bytes = [0, 0, 0x04, 0, 0, 0x01, 0, 0, 0, 3, 8, 0, 0x40, 0xC0];
+        let aspi = AudioSeekPointIndex::parse(&bytes);
+        assert_eq!(
+            aspi,
+            Ok(AudioSeekPointIndex {
+                indexed_data_start: 1024,
+                indexed_data_length: 65536,
+                bits_per_index_point: 8,
+                fractions: vec![0, 0x40, 0xC0],
+            })
+        );
+        let aspi = aspi.unwrap();
+        assert_eq!(aspi.byte_offset(1), Some(1024 + 16384));
+        assert_eq!(aspi.to_bytes(), Ok(bytes.to_vec()));
+    }
+
+    #[test]
+    fn generate_from_frames() {
+        // 128 kbit/s frames of 417 bytes, then 320 kbit/s frames of 1044 bytes
+        let header = |first_byte: u8| MpegFrameHeader::parse([0xFF, 0xFB, first_byte, 0]).unwrap();
+        let headers = [header(0x90), header(0x90), header(0xE0), header(0xE0)];
+        let mut offset = 0;
+        let frames = headers
+            .into_iter()
+            .map(|header| {
+                let frame = MpegFrame { offset, header };
+                offset += frame.header.frame_length() as u64;
+                frame
+            })
+            .collect::<Vec<MpegFrame>>();
+
+        let aspi = AudioSeekPointIndex::generate(&frames, 100, 2, 16).unwrap();
+        assert_eq!(aspi.indexed_data_length, 2922);
+        assert_eq!(aspi.fractions[0], 0);
+        // The second half starts with the first 320 kbit/s frame
+        assert_eq!(aspi.byte_offset(1), Some(100 + 834));
+
+        let bytes = aspi.to_bytes().unwrap();
+        assert_eq!(AudioSeekPointIndex::parse(&bytes), Ok(aspi));
+    }
+}
@@ -0,0 +1,73 @@
+use thiserror::Error;
+
+use crate::tag::encoding::{Encoding, EncodingError};
+
+/// Content of a COMM frame, and of a USLT frame, which holds unsynchronised lyrics with the same
+/// layout. There may be several of them in a tag, but only one with the same language and
+/// description.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Comment {
+    pub encoding: Encoding,
+    /// ISO-639-2 language code, "XXX" when unknown
+    pub language: [u8; 3],
+    pub description: String,
+    pub text: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum CommentError {
+    #[error("Could not read enough bytes to parse the encoding and language")]
+    MissingData,
+    #[error("Encoding Error: {0}")]
+    EncodingError(EncodingError),
+}
+
+impl Comment {
+    pub fn parse(bytes: &[u8]) -> Result<Self, CommentError> {
+        let [encoding_byte, l1, l2, l3, strings @ ..] = bytes else {
+            return Err(CommentError::MissingData);
+        };
+        let encoding =
+            Encoding::from_encoding_byte(*encoding_byte).map_err(CommentError::EncodingError)?;
+        let (description, text) = encoding.read_string(strings);
+        let (text, _) = encoding.read_string(text);
+
+        Ok(Comment {
+            encoding,
+            language: [*l1, *l2, *l3],
+            description,
+            text,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.encoding.encoding_byte()];
+        bytes.extend_from_slice(&self.language);
+        bytes.extend(self.encoding.encode_string(&self.description));
+        bytes.extend_from_slice(self.encoding.string_separator());
+        bytes.extend(self.encoding.encode_string(&self.text));
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_comment() {
+        let bytes = b"\x00engSource\x00Ripped from vinyl".to_vec();
+        let comm = Comment::parse(&bytes);
+        assert_eq!(
+            comm,
+            Ok(Comment {
+                encoding: Encoding::Latin1,
+                language: *b"eng",
+                description: String::from("Source"),
+                text: String::from("Ripped from vinyl"),
+            })
+        );
+        assert_eq!(comm.unwrap().to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,410 @@
+use thiserror::Error;
+
+use crate::{
+    tag::encoding::{Encoding, EncodingError},
+    utils::latin1_to_string,
+};
+
+/// A price made of a three letter ISO-4217 currency code followed by the amount, such as
+/// `USD10.00`. The decimal separator of the amount is always a dot.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Price {
+    pub currency: String,
+    pub amount: String,
+}
+
+impl Price {
+    fn parse(string: &str) -> Result<Self, CommercialError> {
+        let is_valid = string.len() > 3
+            && string.is_char_boundary(3)
+            && string[..3].chars().all(|char| char.is_ascii_uppercase());
+        if !is_valid {
+            return Err(CommercialError::InvalidPrice(string.to_string()));
+        }
+
+        Ok(Price {
+            currency: string[..3].to_string(),
+            amount: string[3..].to_string(),
+        })
+    }
+
+    fn parse_list(string: &str) -> Result<Vec<Self>, CommercialError> {
+        if string.is_empty() {
+            return Ok(vec![]);
+        }
+        string.split('/').map(Price::parse).collect()
+    }
+
+    fn list_to_string(prices: &[Price]) -> String {
+        prices
+            .iter()
+            .map(|price| price.to_string())
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+}
+
+impl std::fmt::Display for Price {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.currency, self.amount)
+    }
+}
+
+/// An 8 character date string, in the format YYYYMMDD.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    fn parse(bytes: &[u8]) -> Result<Self, CommercialError> {
+        let string = latin1_to_string(bytes);
+        let invalid = || CommercialError::InvalidDate(string.clone());
+        if bytes.len() != 8 || !bytes.iter().all(u8::is_ascii_digit) {
+            return Err(invalid());
+        }
+
+        let date = Date {
+            year: string[0..4].parse().map_err(|_| invalid())?,
+            month: string[4..6].parse().map_err(|_| invalid())?,
+            day: string[6..8].parse().map_err(|_| invalid())?,
+        };
+        if !(1..=12).contains(&date.month) || !(1..=date.days_in_month()).contains(&date.day) {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+
+    fn days_in_month(&self) -> u8 {
+        let is_leap_year = self.year.is_multiple_of(4)
+            && (!self.year.is_multiple_of(100) || self.year.is_multiple_of(400));
+        match self.month {
+            2 if is_leap_year => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ReceivedAs {
+    Other,
+    StandardCdAlbumWithOtherSongs,
+    CompressedAudioOnCd,
+    FileOverTheInternet,
+    StreamOverTheInternet,
+    AsNoteSheets,
+    AsNoteSheetsInABookWithOtherSheets,
+    MusicOnOtherMedia,
+    NonMusicalMerchandise,
+    Unknown(u8),
+}
+
+impl From<u8> for ReceivedAs {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => ReceivedAs::Other,
+            0x01 => ReceivedAs::StandardCdAlbumWithOtherSongs,
+            0x02 => ReceivedAs::CompressedAudioOnCd,
+            0x03 => ReceivedAs::FileOverTheInternet,
+            0x04 => ReceivedAs::StreamOverTheInternet,
+            0x05 => ReceivedAs::AsNoteSheets,
+            0x06 => ReceivedAs::AsNoteSheetsInABookWithOtherSheets,
+            0x07 => ReceivedAs::MusicOnOtherMedia,
+            0x08 => ReceivedAs::NonMusicalMerchandise,
+            _ => ReceivedAs::Unknown(value),
+        }
+    }
+}
+
+impl ReceivedAs {
+    pub fn binary_representation(&self) -> u8 {
+        match self {
+            ReceivedAs::Other => 0x00,
+            ReceivedAs::StandardCdAlbumWithOtherSongs => 0x01,
+            ReceivedAs::CompressedAudioOnCd => 0x02,
+            ReceivedAs::FileOverTheInternet => 0x03,
+            ReceivedAs::StreamOverTheInternet => 0x04,
+            ReceivedAs::AsNoteSheets => 0x05,
+            ReceivedAs::AsNoteSheetsInABookWithOtherSheets => 0x06,
+            ReceivedAs::MusicOnOtherMedia => 0x07,
+            ReceivedAs::NonMusicalMerchandise => 0x08,
+            ReceivedAs::Unknown(byte) => *byte,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SellerLogo {
+    /// Only "image/png" and "image/jpeg" are allowed by the spec
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Content of a COMR frame.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Commercial {
+    pub encoding: Encoding,
+    pub prices: Vec<Price>,
+    pub valid_until: Date,
+    pub contact_url: String,
+    pub received_as: ReceivedAs,
+    pub seller_name: String,
+    pub description: String,
+    pub seller_logo: Option<SellerLogo>,
+}
+
+/// Content of an OWNE frame.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Ownership {
+    pub encoding: Encoding,
+    pub price_paid: Option<Price>,
+    pub date_of_purchase: Date,
+    pub seller: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum CommercialError {
+    #[error("Could not read enough bytes to parse the data")]
+    MissingData,
+    #[error("Encoding Error: {0}")]
+    EncodingError(EncodingError),
+    #[error("The price {0} should be a 3 letter currency code followed by the amount")]
+    InvalidPrice(String),
+    #[error("The date {0} should be 8 digits in the format YYYYMMDD")]
+    InvalidDate(String),
+}
+
+fn split_encoding(bytes: &[u8]) -> Result<(Encoding, &[u8]), CommercialError> {
+    let Some((encoding_byte, bytes)) = bytes.split_first() else {
+        return Err(CommercialError::MissingData);
+    };
+    let encoding =
+        Encoding::from_encoding_byte(*encoding_byte).map_err(CommercialError::EncodingError)?;
+    Ok((encoding, bytes))
+}
+
+fn encode(encoding: &Encoding, string: &str) -> Result<Vec<u8>, CommercialError> {
+    encoding
+        .try_encode_string(string)
+        .map_err(CommercialError::EncodingError)
+}
+
+fn encode_latin1(string: &str) -> Result<Vec<u8>, CommercialError> {
+    encode(&Encoding::Latin1, string)
+}
+
+fn split_date(bytes: &[u8]) -> Result<(Date, &[u8]), CommercialError> {
+    if bytes.len() < 8 {
+        return Err(CommercialError::MissingData);
+    }
+    Ok((Date::parse(&bytes[..8])?, &bytes[8..]))
+}
+
+impl Commercial {
+    pub fn parse(bytes: &[u8]) -> Result<Self, CommercialError> {
+        let (encoding, bytes) = split_encoding(bytes)?;
+        let (prices, bytes) = Encoding::Latin1.read_string(bytes);
+        let prices = Price::parse_list(&prices)?;
+        let (valid_until, bytes) = split_date(bytes)?;
+        let (contact_url, bytes) = Encoding::Latin1.read_string(bytes);
+        let Some((received_as, bytes)) = bytes.split_first() else {
+            return Err(CommercialError::MissingData);
+        };
+        let (seller_name, bytes) = encoding.read_string(bytes);
+        let (description, bytes) = encoding.read_string(bytes);
+
+        let seller_logo = if bytes.is_empty() {
+            None
+        } else {
+            let (mime_type, data) = Encoding::Latin1.read_string(bytes);
+            Some(SellerLogo {
+                mime_type,
+                data: data.to_vec(),
+            })
+        };
+
+        Ok(Commercial {
+            encoding,
+            prices,
+            valid_until,
+            contact_url,
+            received_as: ReceivedAs::from(*received_as),
+            seller_name,
+            description,
+            seller_logo,
+        })
+    }
+
+    /// Fails when a string can't be represented in its encoding, which is ISO-8859-1 for the
+    /// prices, contact URL and MIME type.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CommercialError> {
+        let separator = self.encoding.string_separator();
+        let mut bytes = vec![self.encoding.encoding_byte()];
+        bytes.extend(encode_latin1(&Price::list_to_string(&self.prices))?);
+        bytes.push(0);
+        bytes.extend(encode_latin1(&self.valid_until.to_string())?);
+        bytes.extend(encode_latin1(&self.contact_url)?);
+        bytes.push(0);
+        bytes.push(self.received_as.binary_representation());
+        bytes.extend(encode(&self.encoding, &self.seller_name)?);
+        bytes.extend_from_slice(separator);
+        bytes.extend(encode(&self.encoding, &self.description)?);
+        bytes.extend_from_slice(separator);
+        if let Some(logo) = &self.seller_logo {
+            bytes.extend(encode_latin1(&logo.mime_type)?);
+            bytes.push(0);
+            bytes.extend_from_slice(&logo.data);
+        }
+        Ok(bytes)
+    }
+}
+
+impl Ownership {
+    pub fn parse(bytes: &[u8]) -> Result<Self, CommercialError> {
+        let (encoding, bytes) = split_encoding(bytes)?;
+        let (price_paid, bytes) = Encoding::Latin1.read_string(bytes);
+        let price_paid = match price_paid.is_empty() {
+            true => None,
+            false => Some(Price::parse(&price_paid)?),
+        };
+        let (date_of_purchase, bytes) = split_date(bytes)?;
+        let (seller, _) = encoding.read_string(bytes);
+
+        Ok(Ownership {
+            encoding,
+            price_paid,
+            date_of_purchase,
+            seller,
+        })
+    }
+
+    /// Fails when a string can't be represented in its encoding, which is ISO-8859-1 for the
+    /// price.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CommercialError> {
+        let mut bytes = vec![self.encoding.encoding_byte()];
+        if let Some(price_paid) = &self.price_paid {
+            bytes.extend(encode_latin1(&price_paid.to_string())?);
+        }
+        bytes.push(0);
+        bytes.extend(encode_latin1(&self.date_of_purchase.to_string())?);
+        bytes.extend(encode(&self.encoding, &self.seller)?);
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::encoding::ByteOrder;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn commercial_with_logo() {
+        let mut bytes = vec![1];
+        bytes.extend(b"USD10.00/EUR9.50\x0020241231https://shop.example\x00\x03");
+        bytes.extend([0xFF, 0xFE, b'S', 0, b'h', 0, b'o', 0, b'p', 0, 0, 0]);
+        bytes.extend([0xFF, 0xFE, b'A', 0, 0, 0]);
+        bytes.extend(b"image/png\x00\x89PNG");
+
+        let comr = Commercial::parse(&bytes);
+        assert_eq!(
+            comr,
+            Ok(Commercial {
+                encoding: Encoding::UTF16(ByteOrder::LittleEndian),
+                prices: vec![
+                    Price {
+                        currency: String::from("USD"),
+                        amount: String::from("10.00")
+                    },
+                    Price {
+                        currency: String::from("EUR"),
+                        amount: String::from("9.50")
+                    },
+                ],
+                valid_until: Date {
+                    year: 2024,
+                    month: 12,
+                    day: 31
+                },
+                contact_url: String::from("https://shop.example"),
+                received_as: ReceivedAs::FileOverTheInternet,
+                seller_name: String::from("Shop"),
+                description: String::from("A"),
+                seller_logo: Some(SellerLogo {
+                    mime_type: String::from("image/png"),
+                    data: b"\x89PNG".to_vec()
+                }),
+            })
+        );
+        let mut comr = comr.unwrap();
+        assert_eq!(comr.to_bytes(), Ok(bytes));
+
+        comr.contact_url = String::from("https://магазин.example");
+        assert_eq!(
+            comr.to_bytes(),
+            Err(CommercialError::EncodingError(
+                EncodingError::UnrepresentableCharacter('м')
+            ))
+        );
+    }
+
+    #[test]
+    fn simple_ownership() {
+        let bytes = b"\x00GBP0.99\x0020230102Seller".to_vec();
+        let owne = Ownership::parse(&bytes);
+        assert_eq!(
+            owne,
+            Ok(Ownership {
+                encoding: Encoding::Latin1,
+                price_paid: Some(Price {
+                    currency: String::from("GBP"),
+                    amount: String::from("0.99")
+                }),
+                date_of_purchase: Date {
+                    year: 2023,
+                    month: 1,
+                    day: 2
+                },
+                seller: String::from("Seller"),
+            })
+        );
+        let mut owne = owne.unwrap();
+        assert_eq!(owne.to_bytes(), Ok(bytes));
+
+        owne.seller = String::from("Sélecteur €");
+        assert_eq!(
+            owne.to_bytes(),
+            Err(CommercialError::EncodingError(
+                EncodingError::UnrepresentableCharacter('€')
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_date() {
+        assert_eq!(
+            Ownership::parse(b"\x00\x002023-1-2"),
+            Err(CommercialError::InvalidDate(String::from("2023-1-2")))
+        );
+        assert_eq!(
+            Ownership::parse(b"\x00\x0020230231"),
+            Err(CommercialError::InvalidDate(String::from("20230231")))
+        );
+        assert_eq!(
+            Ownership::parse(b"\x00\x0019000229"),
+            Err(CommercialError::InvalidDate(String::from("19000229")))
+        );
+        assert!(Ownership::parse(b"\x00\x0020240229").is_ok());
+        assert!(Ownership::parse(b"\x00\x0020000229").is_ok());
+    }
+}
@@ -0,0 +1,246 @@
+use thiserror::Error;
+
+use crate::utils::{latin1_to_string, string_to_latin1};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum InterpolationMethod {
+    /// No interpolation is made. A jump from one adjustment level to another occurs in the
+    /// middle between two adjustment points.
+    Band,
+    /// Interpolation between adjustment points is linear.
+    Linear,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AdjustmentPoint {
+    /// in units of 1/2 Hz
+    pub frequency: u16,
+    /// in units of 1/512 dB
+    pub volume_adjustment: i16,
+}
+
+impl AdjustmentPoint {
+    /// in Hz
+    pub fn frequency_hz(&self) -> f32 {
+        self.frequency as f32 / 2.0
+    }
+
+    /// in dB
+    pub fn volume_adjustment_db(&self) -> f32 {
+        self.volume_adjustment as f32 / 512.0
+    }
+}
+
+/// Content of an EQU2 frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Equalisation {
+    pub interpolation_method: InterpolationMethod,
+    pub identification: String,
+    pub adjustment_points: Vec<AdjustmentPoint>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum EqualisationError {
+    #[error("Could not read enough bytes to parse the interpolation method")]
+    MissingInterpolationMethod,
+    #[error("The interpolation method byte {0} is unknown. It should either be 00 or 01")]
+    UnknownInterpolationMethod(u8),
+    #[error("The identification string is not terminated")]
+    UnterminatedIdentification,
+    #[error("The adjustment points have {0} trailing bytes, but each point must be 4 bytes long")]
+    TruncatedAdjustmentPoint(usize),
+    #[error("Could not read enough bytes to parse the adjustment bits")]
+    MissingAdjustmentBits,
+    #[error("Adjustments of {0} bits are not supported. It should be between 1 and 32")]
+    UnsupportedAdjustmentBits(u8),
+    #[error("The adjustment {0} does not fit in {1} bits")]
+    AdjustmentTooLarge(u32, u8),
+    #[error("The frequency {0} does not fit in 15 bits")]
+    FrequencyTooLarge(u16),
+}
+
+impl Equalisation {
+    pub fn parse(bytes: &[u8]) -> Result<Self, EqualisationError> {
+        let Some((method_byte, bytes)) = bytes.split_first() else {
+            return Err(EqualisationError::MissingInterpolationMethod);
+        };
+        let interpolation_method = match method_byte {
+            0 => InterpolationMethod::Band,
+            1 => InterpolationMethod::Linear,
+            _ => return Err(EqualisationError::UnknownInterpolationMethod(*method_byte)),
+        };
+
+        let Some(position) = bytes.iter().position(|byte| *byte == 0) else {
+            return Err(EqualisationError::UnterminatedIdentification);
+        };
+        let identification = latin1_to_string(&bytes[..position]);
+
+        let chunks = bytes[position + 1..].chunks_exact(4);
+        if !chunks.remainder().is_empty() {
+            return Err(EqualisationError::TruncatedAdjustmentPoint(
+                chunks.remainder().len(),
+            ));
+        }
+        let adjustment_points = chunks
+            .map(|chunk| AdjustmentPoint {
+                frequency: u16::from_be_bytes([chunk[0], chunk[1]]),
+                volume_adjustment: i16::from_be_bytes([chunk[2], chunk[3]]),
+            })
+            .collect();
+
+        Ok(Equalisation {
+            interpolation_method,
+            identification,
+            adjustment_points,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![match self.interpolation_method {
+            InterpolationMethod::Band => 0,
+            InterpolationMethod::Linear => 1,
+        }];
+        bytes.extend(string_to_latin1(&self.identification));
+        bytes.push(0);
+        for point in &self.adjustment_points {
+            bytes.extend_from_slice(&point.frequency.to_be_bytes());
+            bytes.extend_from_slice(&point.volume_adjustment.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AdjustmentPointV23 {
+    /// `true` for increment, `false` for decrement
+    pub increment: bool,
+    /// in Hz, 15 bits at most
+    pub frequency: u16,
+    pub adjustment: u32,
+}
+
+/// Content of an ID3v2.3 EQUA frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EqualisationV23 {
+    pub adjustment_bits: u8,
+    pub adjustment_points: Vec<AdjustmentPointV23>,
+}
+
+impl EqualisationV23 {
+    fn adjustment_bytes(adjustment_bits: u8) -> Result<usize, EqualisationError> {
+        if adjustment_bits == 0 || adjustment_bits > 32 {
+            return Err(EqualisationError::UnsupportedAdjustmentBits(
+                adjustment_bits,
+            ));
+        }
+        Ok((adjustment_bits as usize).div_ceil(8))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, EqualisationError> {
+        let Some((adjustment_bits, bytes)) = bytes.split_first() else {
+            return Err(EqualisationError::MissingAdjustmentBits);
+        };
+        let adjustment_bytes = Self::adjustment_bytes(*adjustment_bits)?;
+
+        let chunks = bytes.chunks_exact(2 + adjustment_bytes);
+        if !chunks.remainder().is_empty() {
+            return Err(EqualisationError::TruncatedAdjustmentPoint(
+                chunks.remainder().len(),
+            ));
+        }
+        let adjustment_points = chunks
+            .map(|chunk| AdjustmentPointV23 {
+                increment: chunk[0] & 0x80 != 0,
+                frequency: u16::from_be_bytes([chunk[0] & 0x7F, chunk[1]]),
+                adjustment: chunk[2..]
+                    .iter()
+                    .fold(0u32, |value, byte| (value << 8) | *byte as u32),
+            })
+            .collect();
+
+        Ok(EqualisationV23 {
+            adjustment_bits: *adjustment_bits,
+            adjustment_points,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EqualisationError> {
+        let adjustment_bytes = Self::adjustment_bytes(self.adjustment_bits)?;
+        let mut bytes = vec![self.adjustment_bits];
+        for point in &self.adjustment_points {
+            if point.frequency > 0x7FFF {
+                return Err(EqualisationError::FrequencyTooLarge(point.frequency));
+            }
+            if self.adjustment_bits < 32 && point.adjustment >> self.adjustment_bits != 0 {
+                return Err(EqualisationError::AdjustmentTooLarge(
+                    point.adjustment,
+                    self.adjustment_bits,
+                ));
+            }
+            let frequency = point.frequency | ((point.increment as u16) << 15);
+            bytes.extend_from_slice(&frequency.to_be_bytes());
+            bytes.extend_from_slice(&point.adjustment.to_be_bytes()[4 - adjustment_bytes..]);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_equalisation() {
+        let bytes = [
+            1, b'H', b'i', b'F', b'i', 0, 0x03, 0xE8, 0xFE, 0x00, 0x4E, 0x20, 0x04, 0x00,
+        ];
+        let equ2 = Equalisation::parse(&bytes);
+        assert_eq!(
+            equ2,
+            Ok(Equalisation {
+                interpolation_method: InterpolationMethod::Linear,
+                identification: String::from("HiFi"),
+                adjustment_points: vec![
+                    AdjustmentPoint {
+                        frequency: 1000,
+                        volume_adjustment: -512
+                    },
+                    AdjustmentPoint {
+                        frequency: 20000,
+                        volume_adjustment: 1024
+                    },
+                ]
+            })
+        );
+        let equ2 = equ2.unwrap();
+        assert_eq!(equ2.adjustment_points[0].frequency_hz(), 500.0);
+        assert_eq!(equ2.adjustment_points[0].volume_adjustment_db(), -1.0);
+        assert_eq!(equ2.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn v23_equalisation() {
+        let bytes = [16, 0x80, 0x64, 0x01, 0x00, 0x1F, 0x40, 0x00, 0x80];
+        let equa = EqualisationV23::parse(&bytes);
+        assert_eq!(
+            equa,
+            Ok(EqualisationV23 {
+                adjustment_bits: 16,
+                adjustment_points: vec![
+                    AdjustmentPointV23 {
+                        increment: true,
+                        frequency: 100,
+                        adjustment: 256
+                    },
+                    AdjustmentPointV23 {
+                        increment: false,
+                        frequency: 8000,
+                        adjustment: 128
+                    },
+                ]
+            })
+        );
+        assert_eq!(equa.unwrap().to_bytes(), Ok(bytes.to_vec()));
+    }
+}
@@ -0,0 +1,216 @@
+use thiserror::Error;
+
+use super::timestamp_format::{TimestampFormat, TimestampFormatError};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EventType {
+    /// Has no meaning
+    Padding,
+    EndOfInitialSilence,
+    IntroStart,
+    MainPartStart,
+    OutroStart,
+    OutroEnd,
+    VerseStart,
+    RefrainStart,
+    InterludeStart,
+    ThemeStart,
+    VariationStart,
+    KeyChange,
+    TimeChange,
+    /// Snap, Crackle & Pop
+    MomentaryUnwantedNoise,
+    SustainedNoise,
+    SustainedNoiseEnd,
+    IntroEnd,
+    MainPartEnd,
+    VerseEnd,
+    RefrainEnd,
+    ThemeEnd,
+    Profanity,
+    ProfanityEnd,
+    /// Not predefined synch 0-F. Holds the low nibble of the event byte.
+    NotPredefinedSynch(u8),
+    /// Start of silence
+    AudioEnd,
+    AudioFileEnds,
+    /// One more byte of events follows
+    OneMoreByteOfEvents,
+    /// Values reserved for future use by the spec.
+    Reserved(u8),
+}
+
+impl From<u8> for EventType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => EventType::Padding,
+            0x01 => EventType::EndOfInitialSilence,
+            0x02 => EventType::IntroStart,
+            0x03 => EventType::MainPartStart,
+            0x04 => EventType::OutroStart,
+            0x05 => EventType::OutroEnd,
+            0x06 => EventType::VerseStart,
+            0x07 => EventType::RefrainStart,
+            0x08 => EventType::InterludeStart,
+            0x09 => EventType::ThemeStart,
+            0x0A => EventType::VariationStart,
+            0x0B => EventType::KeyChange,
+            0x0C => EventType::TimeChange,
+            0x0D => EventType::MomentaryUnwantedNoise,
+            0x0E => EventType::SustainedNoise,
+            0x0F => EventType::SustainedNoiseEnd,
+            0x10 => EventType::IntroEnd,
+            0x11 => EventType::MainPartEnd,
+            0x12 => EventType::VerseEnd,
+            0x13 => EventType::RefrainEnd,
+            0x14 => EventType::ThemeEnd,
+            0x15 => EventType::Profanity,
+            0x16 => EventType::ProfanityEnd,
+            0xE0..=0xEF => EventType::NotPredefinedSynch(value & 0x0F),
+            0xFD => EventType::AudioEnd,
+            0xFE => EventType::AudioFileEnds,
+            0xFF => EventType::OneMoreByteOfEvents,
+            _ => EventType::Reserved(value),
+        }
+    }
+}
+
+impl EventType {
+    pub fn binary_representation(&self) -> u8 {
+        match self {
+            EventType::Padding => 0x00,
+            EventType::EndOfInitialSilence => 0x01,
+            EventType::IntroStart => 0x02,
+            EventType::MainPartStart => 0x03,
+            EventType::OutroStart => 0x04,
+            EventType::OutroEnd => 0x05,
+            EventType::VerseStart => 0x06,
+            EventType::RefrainStart => 0x07,
+            EventType::InterludeStart => 0x08,
+            EventType::ThemeStart => 0x09,
+            EventType::VariationStart => 0x0A,
+            EventType::KeyChange => 0x0B,
+            EventType::TimeChange => 0x0C,
+            EventType::MomentaryUnwantedNoise => 0x0D,
+            EventType::SustainedNoise => 0x0E,
+            EventType::SustainedNoiseEnd => 0x0F,
+            EventType::IntroEnd => 0x10,
+            EventType::MainPartEnd => 0x11,
+            EventType::VerseEnd => 0x12,
+            EventType::RefrainEnd => 0x13,
+            EventType::ThemeEnd => 0x14,
+            EventType::Profanity => 0x15,
+            EventType::ProfanityEnd => 0x16,
+            EventType::NotPredefinedSynch(nibble) => 0xE0 | (nibble & 0x0F),
+            EventType::AudioEnd => 0xFD,
+            EventType::AudioFileEnds => 0xFE,
+            EventType::OneMoreByteOfEvents => 0xFF,
+            EventType::Reserved(byte) => *byte,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Event {
+    pub event_type: EventType,
+    pub timestamp: u32,
+}
+
+/// Content of an ETCO frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EventTimingCodes {
+    pub timestamp_format: TimestampFormat,
+    pub events: Vec<Event>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum EventTimingCodesError {
+    #[error("Could not read enough bytes to parse the time stamp format")]
+    MissingTimestampFormat,
+    #[error("{0}")]
+    TimestampFormatError(TimestampFormatError),
+    #[error("The event list has {0} trailing bytes, but each event must be 5 bytes long")]
+    TruncatedEvent(usize),
+}
+
+impl EventTimingCodes {
+    pub fn parse(bytes: &[u8]) -> Result<Self, EventTimingCodesError> {
+        let Some((format_byte, bytes)) = bytes.split_first() else {
+            return Err(EventTimingCodesError::MissingTimestampFormat);
+        };
+        let timestamp_format = TimestampFormat::try_from(*format_byte)
+            .map_err(EventTimingCodesError::TimestampFormatError)?;
+
+        let chunks = bytes.chunks_exact(5);
+        if !chunks.remainder().is_empty() {
+            return Err(EventTimingCodesError::TruncatedEvent(
+                chunks.remainder().len(),
+            ));
+        }
+
+        let events = chunks
+            .map(|chunk| Event {
+                event_type: EventType::from(chunk[0]),
+                timestamp: u32::from_be_bytes([chunk[1], chunk[2], chunk[3], chunk[4]]),
+            })
+            .collect();
+
+        Ok(EventTimingCodes {
+            timestamp_format,
+            events,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.events.len() * 5);
+        bytes.push(self.timestamp_format.binary_representation());
+        for event in &self.events {
+            bytes.push(event.event_type.binary_representation());
+            bytes.extend_from_slice(&event.timestamp.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_event_timing_codes() {
+        let bytes = [
+            2, 0x02, 0, 0, 0, 0, 0x0B, 0, 0, 0x03, 0xE8, 0xE3, 0, 0, 0x07, 0xD0,
+        ];
+        let etco = EventTimingCodes::parse(&bytes);
+        assert_eq!(
+            etco,
+            Ok(EventTimingCodes {
+                timestamp_format: TimestampFormat::Milliseconds,
+                events: vec![
+                    Event {
+                        event_type: EventType::IntroStart,
+                        timestamp: 0
+                    },
+                    Event {
+                        event_type: EventType::KeyChange,
+                        timestamp: 1000
+                    },
+                    Event {
+                        event_type: EventType::NotPredefinedSynch(3),
+                        timestamp: 2000
+                    },
+                ]
+            })
+        );
+        assert_eq!(etco.unwrap().to_bytes(), bytes);
+    }
+
+    #[test]
+    fn truncated_event() {
+        assert_eq!(
+            EventTimingCodes::parse(&[1, 0x02, 0, 0]),
+            Err(EventTimingCodesError::TruncatedEvent(3))
+        );
+    }
+}
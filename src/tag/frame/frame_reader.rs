@@ -0,0 +1,261 @@
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    sync::Arc,
+};
+
+use crate::tag::{
+    extended_header::ExtendedHeader,
+    header::{Header, HeaderFlagType},
+    TagReadError,
+};
+
+use super::{frame_id::FrameID, DecodedFrameType, Frame, FrameReadError};
+
+/// Tells which frames to keep from their id.
+pub type FrameFilter = Box<dyn Fn(&FrameID) -> bool + Send + Sync>;
+
+/// Reads the frames of a tag one at a time. Once they are all read, the reader is left past the
+/// padding and footer, as after [`crate::tag::Tag::read`].
+pub struct FrameReader<R> {
+    reader: R,
+    header: Header,
+    extended_header: Option<ExtendedHeader>,
+    /// in bytes, of the tag after the frames read so far
+    remaining: u32,
+    finished: bool,
+    padding: Option<u32>,
+    wanted: Option<FrameFilter>,
+    /// in bytes
+    max_frame_size: Option<u32>,
+    skip: fn(&mut R, u64) -> io::Result<()>,
+}
+
+fn skip_by_reading<R: Read>(reader: &mut R, bytes: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(bytes), &mut io::sink())?;
+    if skipped < bytes {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn skip_by_seeking<R: Seek>(reader: &mut R, bytes: u64) -> io::Result<()> {
+    reader.seek(SeekFrom::Current(bytes as i64)).map(|_| ())
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Reads the header and extended header, leaving the reader at the first frame.
+    pub fn new(mut reader: R) -> Result<Self, TagReadError> {
+        let header = Header::read(&mut reader)?;
+
+        let extended_header = if header.is_flag_set(&HeaderFlagType::ExtendedHeader) {
+            Some(ExtendedHeader::read(&mut reader, header.major_version())?)
+        } else {
+            None
+        };
+
+        // The tag size excludes the header, but includes the extended header
+        let remaining = header.tag_size().saturating_sub(
+            extended_header
+                .as_ref()
+                .map(|xheader| xheader.bytes_size(header.major_version()))
+                .unwrap_or(0),
+        );
+
+        Ok(FrameReader {
+            reader,
+            header,
+            extended_header,
+            remaining,
+            finished: false,
+            padding: None,
+            wanted: None,
+            max_frame_size: None,
+            skip: skip_by_reading,
+        })
+    }
+
+    /// Only yields the frames the predicate returns true for. The data of the others is skipped
+    /// without being kept.
+    pub fn only(mut self, wanted: impl Fn(&FrameID) -> bool + Send + Sync + 'static) -> Self {
+        self.wanted = Some(Box::new(wanted));
+        self
+    }
+
+    /// Skips the frames holding more data than the given size, in bytes.
+    pub fn max_frame_size(mut self, max_frame_size: u32) -> Self {
+        self.max_frame_size = Some(max_frame_size);
+        self
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn extended_header(&self) -> Option<&ExtendedHeader> {
+        self.extended_header.as_ref()
+    }
+
+    /// in bytes, known once every frame is read
+    pub fn padding(&self) -> Option<u32> {
+        self.padding
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Skips the frames left, the padding and the footer without reading them. The padding
+    /// stays unknown.
+    pub fn skip_rest(&mut self) {
+        if !self.finished {
+            self.finish(0);
+            self.padding = None;
+        }
+    }
+
+    fn is_wanted(&self, frame_id: &FrameID, frame_size: u32) -> bool {
+        self.max_frame_size.is_none_or(|max| frame_size <= max)
+            && self.wanted.as_ref().is_none_or(|wanted| wanted(frame_id))
+    }
+
+    /// Skips the padding and footer, and stops reading.
+    fn finish(&mut self, already_read: u32) {
+        let mut rest = (self.remaining - already_read) as u64;
+        if self.header.is_flag_set(&HeaderFlagType::FooterPresent) {
+            rest += self.header.bytes_size() as u64;
+        }
+        // The frames are already read, a truncated padding or footer isn't worth failing for
+        let _ = (self.skip)(&mut self.reader, rest);
+        self.padding = Some(self.remaining);
+        self.remaining = 0;
+        self.finished = true;
+    }
+
+    fn read_frame(&mut self) -> Option<Result<Frame, FrameReadError>> {
+        let major_version = self.header.major_version();
+        loop {
+            if self.finished {
+                return None;
+            }
+            // Too few bytes are left for a frame header, so they can only be padding
+            if self.remaining < 10 {
+                self.finish(0);
+                return None;
+            }
+
+            let (frame_id, frame_size, flags_byte) =
+                match Frame::read_header(&mut self.reader, major_version) {
+                    Ok(frame_header) => frame_header,
+                    Err(FrameReadError::InvalidFrameID(_)) => {
+                        // The padding started with the bytes read as the frame id
+                        self.finish(4);
+                        return None;
+                    }
+                    Err(frame_read_error) => {
+                        self.finished = true;
+                        return Some(Err(frame_read_error));
+                    }
+                };
+            // Checked before anything is allocated, as the size can be anything up to 4 GiB
+            if frame_size > self.remaining - 10 {
+                self.finished = true;
+                return Some(Err(FrameReadError::NotEnoughBytes));
+            }
+            self.remaining -= 10 + frame_size;
+
+            if !self.is_wanted(&frame_id, frame_size) {
+                if (self.skip)(&mut self.reader, frame_size as u64).is_err() {
+                    self.finished = true;
+                    return Some(Err(FrameReadError::NotEnoughBytes));
+                }
+                continue;
+            }
+
+            let mut data = vec![0; frame_size as usize];
+            if self.reader.read_exact(&mut data).is_err() {
+                self.finished = true;
+                return Some(Err(FrameReadError::NotEnoughBytes));
+            }
+            return Some(Ok(Frame {
+                decoded: DecodedFrameType::default(),
+                frame_id,
+                frame_size,
+                flags_byte,
+                data: Arc::new(data),
+            }));
+        }
+    }
+}
+
+impl<R: Read + Seek> FrameReader<R> {
+    /// Seeks past the skipped frames and the padding instead of reading them.
+    pub fn seeking(mut self) -> Self {
+        self.skip = skip_by_seeking;
+        self
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = Result<Frame, FrameReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{builder::TagBuilder, test_utils::frame, Tag};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn only_wanted_frames() {
+        let tag = TagBuilder::new()
+            .padding(12)
+            .frame(frame(b"TIT2", b"\x00Title"))
+            .frame(frame(b"APIC", &[0; 100]))
+            .frame(frame(b"TPE1", b"\x00Artist"))
+            .build()
+            .unwrap();
+        let bytes = [tag.to_bytes(), b"audio".to_vec()].concat();
+
+        let mut reader = FrameReader::new(Cursor::new(&bytes))
+            .unwrap()
+            .only(|id| matches!(id.bytes(), b"TIT2" | b"TPE1"))
+            .seeking();
+        assert_eq!(reader.next(), Some(Ok(frame(b"TIT2", b"\x00Title"))));
+        assert_eq!(reader.next(), Some(Ok(frame(b"TPE1", b"\x00Artist"))));
+        assert_eq!(reader.next(), None);
+        assert_eq!(reader.padding(), Some(12));
+        assert_eq!(reader.into_inner().position(), bytes.len() as u64 - 5);
+
+        let frames = FrameReader::new(&bytes[..])
+            .unwrap()
+            .collect::<Result<Vec<Frame>, FrameReadError>>();
+        assert_eq!(frames.as_deref(), Ok(tag.frames()));
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let tag = Tag::default();
+        let mut bytes = tag.to_bytes();
+        bytes[9] = 30;
+        bytes.extend_from_slice(b"TIT2\x00\x00\x00\x10\x00\x00\x00Tit");
+
+        let mut reader = FrameReader::new(&bytes[..]).unwrap();
+        assert_eq!(reader.next(), Some(Err(FrameReadError::NotEnoughBytes)));
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn frame_larger_than_tag() {
+        // A v2.3 frame size isn't syncsafe, so it can be close to u32::MAX
+        let bytes = b"ID3\x03\x00\x00\x00\x00\x00\x0ATIT2\xFF\xFF\xFF\xFF\x00\x00";
+        let mut reader = FrameReader::new(&bytes[..]).unwrap();
+        assert_eq!(reader.next(), Some(Err(FrameReadError::NotEnoughBytes)));
+        assert_eq!(reader.next(), None);
+    }
+}
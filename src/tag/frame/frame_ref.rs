@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use super::{
+    frame_id::FrameID, frame_type::FrameType, DecodedFrameType, Frame, FrameFlagData, FrameFlags,
+    FrameReadError,
+};
+
+/// A frame borrowing its data from the bytes it was parsed from. Nothing is decoded until asked
+/// for.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FrameRef<'a> {
+    frame_id: FrameID,
+    flags_byte: (u8, u8),
+    data: &'a [u8],
+}
+
+impl<'a> FrameRef<'a> {
+    /// Parses the frame at the start of the bytes, and moves them past it.
+    pub fn parse(bytes: &mut &'a [u8], major_version: u8) -> Result<Self, FrameReadError> {
+        let (frame_id, frame_size, flags_byte) = Frame::read_header(bytes, major_version)?;
+        let Some((data, rest)) = bytes.split_at_checked(frame_size as usize) else {
+            return Err(FrameReadError::NotEnoughBytes);
+        };
+        *bytes = rest;
+
+        Ok(FrameRef {
+            frame_id,
+            flags_byte,
+            data,
+        })
+    }
+
+    pub fn bytes_size(&self) -> u32 {
+        self.data.len() as u32 + 10
+    }
+
+    pub fn frame_id(&self) -> &FrameID {
+        &self.frame_id
+    }
+
+    /// The frame content, including any data appended because of the frame flags.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn flags(&self, major_version: u8) -> FrameFlags {
+        FrameFlags::from_bytes(self.flags_byte, major_version)
+    }
+
+    pub fn flag_data(&self, major_version: u8) -> Result<FrameFlagData, FrameReadError> {
+        FrameFlagData::read(self.data, &self.flags(major_version), major_version)
+    }
+
+    /// The frame content, without the data appended because of the frame flags.
+    pub fn content(&self, major_version: u8) -> Result<&'a [u8], FrameReadError> {
+        let flag_data = self.flag_data(major_version)?;
+        Ok(&self.data[flag_data.size..])
+    }
+
+    /// Decodes the frame content into the type matching the frame id.
+    pub fn frame_type(&self, major_version: u8) -> Result<FrameType, FrameReadError> {
+        Ok(FrameType::parse(
+            &self.frame_id,
+            self.content(major_version)?,
+            major_version,
+        ))
+    }
+
+    /// Copies the data into an owned frame.
+    pub fn to_frame(&self) -> Frame {
+        Frame {
+            decoded: DecodedFrameType::default(),
+            frame_id: self.frame_id.clone(),
+            frame_size: self.data.len() as u32,
+            flags_byte: self.flags_byte,
+            data: Arc::new(self.data.to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn borrowed_frame() {
+        let bytes = b"TIT2\x00\x00\x00\x06\x00\x00\x00TitleTPE1";
+        let mut rest = &bytes[..];
+        let frame = FrameRef::parse(&mut rest, 4).unwrap();
+        assert_eq!(frame.frame_id().bytes(), b"TIT2");
+        assert_eq!(frame.data(), b"\x00Title");
+        assert_eq!(frame.data().as_ptr(), bytes[10..].as_ptr());
+        assert_eq!(rest, b"TPE1");
+        assert_eq!(frame.to_frame(), Frame::read(&mut &bytes[..]).unwrap());
+
+        assert_eq!(
+            FrameRef::parse(&mut &bytes[..15], 4),
+            Err(FrameReadError::NotEnoughBytes)
+        );
+    }
+}
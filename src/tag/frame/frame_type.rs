@@ -1,73 +1,126 @@
-use std::rc::Rc;
-
-use thiserror::Error;
-
-use crate::{
-    tag::encoding::{Encoding, EncodingError},
-    utils::latin1_to_string,
+use super::{
+    attached_picture::{AttachedPicture, AttachedPictureError},
+    audio_encryption::{AudioEncryption, AudioEncryptionError},
+    audio_seek_point_index::{AudioSeekPointIndex, AudioSeekPointIndexError},
+    comment::{Comment, CommentError},
+    commercial::{Commercial, CommercialError, Ownership},
+    equalisation::{Equalisation, EqualisationError, EqualisationV23},
+    event_timing_codes::{EventTimingCodes, EventTimingCodesError},
+    frame_id::FrameID,
+    linked_information::{LinkedInformation, LinkedInformationError},
+    mpeg_location_lookup_table::{MpegLocationLookupTable, MpegLocationLookupTableError},
+    music_cd_identifier::{MusicCdIdentifier, MusicCdIdentifierError},
+    position_synchronisation::{PositionSynchronisation, PositionSynchronisationError},
+    recommended_buffer_size::{RecommendedBufferSize, RecommendedBufferSizeError},
+    registration::{
+        EncryptionMethodRegistration, GroupIdentificationRegistration, RegistrationError,
+    },
+    reverb::{Reverb, ReverbError},
+    seek::{Seek, SeekError},
+    signature::{Signature, SignatureFrameError},
+    synchronised_tempo_codes::{SynchronisedTempoCodes, SynchronisedTempoCodesError},
+    terms_of_use::{TermsOfUse, TermsOfUseError},
+    text_information::{TextInformation, TextInformationError},
+    unique_file_identifier::{UniqueFileIdentifier, UniqueFileIdentifierError},
+    Frame,
 };
 
-use super::Frame;
-
-#[derive(PartialEq, Clone, Debug, Error)]
-pub enum TextInformationError {
-    #[error("Could not read enough bytes to parse the data")]
-    MissingData,
-    #[error("Encoding Error: {0}")]
-    EncodingError(EncodingError),
-}
-
-#[derive(PartialEq, Clone, Debug)]
-pub struct TextInformation {
-    encoding: Encoding,
-    strings: Vec<String>,
-}
-
 #[derive(PartialEq, Clone, Debug, Default)]
 pub enum FrameType {
     #[default]
     Unknown,
-    UniqueFileIdentifier {
-        owner_identifier: String,
-        identifier: Rc<[u8]>,
-    },
+    UniqueFileIdentifier(Result<UniqueFileIdentifier, UniqueFileIdentifierError>),
     TextInformation(Result<TextInformation, TextInformationError>),
+    EventTimingCodes(Result<EventTimingCodes, EventTimingCodesError>),
+    SynchronisedTempoCodes(Result<SynchronisedTempoCodes, SynchronisedTempoCodesError>),
+    MpegLocationLookupTable(Result<MpegLocationLookupTable, MpegLocationLookupTableError>),
+    Equalisation(Result<Equalisation, EqualisationError>),
+    EqualisationV23(Result<EqualisationV23, EqualisationError>),
+    Reverb(Result<Reverb, ReverbError>),
+    Commercial(Result<Commercial, CommercialError>),
+    Ownership(Result<Ownership, CommercialError>),
+    TermsOfUse(Result<TermsOfUse, TermsOfUseError>),
+    EncryptionMethodRegistration(Result<EncryptionMethodRegistration, RegistrationError>),
+    GroupIdentificationRegistration(Result<GroupIdentificationRegistration, RegistrationError>),
+    Signature(Result<Signature, SignatureFrameError>),
+    AudioEncryption(Result<AudioEncryption, AudioEncryptionError>),
+    AudioSeekPointIndex(Result<AudioSeekPointIndex, AudioSeekPointIndexError>),
+    LinkedInformation(Result<LinkedInformation, LinkedInformationError>),
+    PositionSynchronisation(Result<PositionSynchronisation, PositionSynchronisationError>),
+    RecommendedBufferSize(Result<RecommendedBufferSize, RecommendedBufferSizeError>),
+    Seek(Result<Seek, SeekError>),
+    MusicCdIdentifier(Result<MusicCdIdentifier, MusicCdIdentifierError>),
+    Comment(Result<Comment, CommentError>),
+    UnsynchronisedLyrics(Result<Comment, CommentError>),
+    AttachedPicture(Result<AttachedPicture, AttachedPictureError>),
     Experimental,
 }
 
 impl From<Frame> for FrameType {
     fn from(value: Frame) -> Self {
-        let id = value.frame_id.bytes();
-        let data = value.data;
+        FrameType::parse(&value.frame_id, &value.data, 4)
+    }
+}
+
+impl FrameType {
+    /// Decodes the content of a frame of a tag with the given major version, without the data
+    /// appended because of the frame flags.
+    pub fn parse(frame_id: &FrameID, data: &[u8], major_version: u8) -> Self {
+        let id = frame_id.bytes();
 
         match id {
-            b"UFID" => {
-                let position = data.iter().position(|e| *e == 0).unwrap_or(data.len());
-                let owner_identifier = latin1_to_string(&data[..position]);
-                let identifier = Rc::from(&data[position + 1..]);
-                FrameType::UniqueFileIdentifier {
-                    owner_identifier,
-                    identifier,
-                }
+            b"UFID" => FrameType::UniqueFileIdentifier(UniqueFileIdentifier::parse(data)),
+            b"ETCO" => FrameType::EventTimingCodes(EventTimingCodes::parse(data)),
+            b"SYTC" => FrameType::SynchronisedTempoCodes(SynchronisedTempoCodes::parse(data)),
+            b"MLLT" => FrameType::MpegLocationLookupTable(MpegLocationLookupTable::parse(data)),
+            b"EQU2" => FrameType::Equalisation(Equalisation::parse(data)),
+            b"EQUA" => FrameType::EqualisationV23(EqualisationV23::parse(data)),
+            b"RVRB" => FrameType::Reverb(Reverb::parse(data)),
+            b"COMR" => FrameType::Commercial(Commercial::parse(data)),
+            b"OWNE" => FrameType::Ownership(Ownership::parse(data)),
+            b"USER" => FrameType::TermsOfUse(TermsOfUse::parse(data)),
+            b"ENCR" => {
+                FrameType::EncryptionMethodRegistration(EncryptionMethodRegistration::parse(data))
             }
+            b"GRID" => FrameType::GroupIdentificationRegistration(
+                GroupIdentificationRegistration::parse(data),
+            ),
+            b"SIGN" => FrameType::Signature(Signature::parse(data)),
+            b"AENC" => FrameType::AudioEncryption(AudioEncryption::parse(data)),
+            b"ASPI" => FrameType::AudioSeekPointIndex(AudioSeekPointIndex::parse(data)),
+            b"LINK" => FrameType::LinkedInformation(LinkedInformation::parse(data, major_version)),
+            b"POSS" => FrameType::PositionSynchronisation(PositionSynchronisation::parse(data)),
+            b"RBUF" => FrameType::RecommendedBufferSize(RecommendedBufferSize::parse(data)),
+            b"SEEK" => FrameType::Seek(Seek::parse(data)),
+            b"MCDI" => FrameType::MusicCdIdentifier(MusicCdIdentifier::parse(data)),
+            b"COMM" => FrameType::Comment(Comment::parse(data)),
+            b"USLT" => FrameType::UnsynchronisedLyrics(Comment::parse(data)),
+            b"APIC" => FrameType::AttachedPicture(AttachedPicture::parse(data)),
             _ if id[0] == b'X' || id[0] == b'Y' || id[0] == b'Z' => FrameType::Experimental,
-            _ if id[1] == b'T' => FrameType::TextInformation('struct_result: {
-                let encoding = match Encoding::extract_from_vec(&data) {
-                    Ok(encoding) => encoding,
-                    Err(err) => break 'struct_result Err(TextInformationError::EncodingError(err)),
-                };
-                let data = &data[encoding.bytes_length()..];
-                let splits = encoding.split_bytes_by_string_separator(data);
-                let strings = splits
-                    .into_iter()
-                    .map(|split| encoding.make_string(split))
-                    .collect();
-
-                Ok(TextInformation { encoding, strings })
-            }),
+            _ if id[0] == b'T' => FrameType::TextInformation(TextInformation::parse(data)),
             _ => FrameType::Unknown,
         }
     }
 }
 
-impl FrameType {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(id: &[u8; 4], data: &[u8]) -> FrameType {
+        FrameType::parse(&FrameID::try_from(*id).unwrap(), data, 4)
+    }
+
+    #[test]
+    fn text_frames_by_first_letter() {
+        assert!(matches!(
+            parse(b"TIT2", b"\x00Title"),
+            FrameType::TextInformation(Ok(_))
+        ));
+        assert!(matches!(
+            parse(b"TXXX", b"\x00Mood\x00Calm"),
+            FrameType::TextInformation(Ok(_))
+        ));
+        assert!(matches!(parse(b"ATXX", b"\x00Title"), FrameType::Unknown));
+    }
+}
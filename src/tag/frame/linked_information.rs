@@ -0,0 +1,121 @@
+use thiserror::Error;
+
+use crate::{tag::encoding::Encoding, utils::string_to_latin1};
+
+/// Content of a LINK frame.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LinkedInformation {
+    /// The frame that should be fetched from the URL. Its id has 3 bytes in ID3v2.3, and 4 since
+    /// ID3v2.4.
+    pub frame_id: Vec<u8>,
+    pub url: String,
+    /// Additional strings needed to identify the linked frame, such as a language or description
+    pub additional_data: Vec<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum LinkedInformationError {
+    #[error("Could not read enough bytes to parse the linked frame identifier")]
+    MissingData,
+    #[error("The linked frame id {0} must only have capital letters or numbers")]
+    InvalidFrameID(String),
+    #[error("The linked frame id {0} must have {1} bytes in this version")]
+    WrongFrameIDLength(String, usize),
+}
+
+/// Bytes of the linked frame id in a tag of the given major version.
+fn frame_id_length(major_version: u8) -> usize {
+    if major_version == 3 {
+        3
+    } else {
+        4
+    }
+}
+
+impl LinkedInformation {
+    pub fn parse(bytes: &[u8], major_version: u8) -> Result<Self, LinkedInformationError> {
+        let Some((frame_id, bytes)) = bytes.split_at_checked(frame_id_length(major_version)) else {
+            return Err(LinkedInformationError::MissingData);
+        };
+        if !frame_id
+            .iter()
+            .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+        {
+            return Err(LinkedInformationError::InvalidFrameID(
+                String::from_utf8_lossy(frame_id).to_string(),
+            ));
+        }
+        let (url, mut bytes) = Encoding::Latin1.read_string(bytes);
+
+        let mut additional_data = vec![];
+        while !bytes.is_empty() {
+            let (string, rest) = Encoding::Latin1.read_string(bytes);
+            additional_data.push(string);
+            bytes = rest;
+        }
+
+        Ok(LinkedInformation {
+            frame_id: frame_id.to_vec(),
+            url,
+            additional_data,
+        })
+    }
+
+    pub fn to_bytes(&self, major_version: u8) -> Result<Vec<u8>, LinkedInformationError> {
+        let frame_id_length = frame_id_length(major_version);
+        if self.frame_id.len() != frame_id_length {
+            return Err(LinkedInformationError::WrongFrameIDLength(
+                String::from_utf8_lossy(&self.frame_id).to_string(),
+                frame_id_length,
+            ));
+        }
+
+        let mut bytes = self.frame_id.clone();
+        bytes.extend(string_to_latin1(&self.url));
+        bytes.push(0);
+        let additional_data = self
+            .additional_data
+            .iter()
+            .map(|string| string_to_latin1(string))
+            .collect::<Vec<Vec<u8>>>();
+        bytes.extend(additional_data.join(&0));
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn link_with_additional_data() {
+        let bytes = b"COMMhttp://example.com/tags\x00eng\x00Review".to_vec();
+        let link = LinkedInformation::parse(&bytes, 4);
+        assert_eq!(
+            link,
+            Ok(LinkedInformation {
+                frame_id: b"COMM".to_vec(),
+                url: String::from("http://example.com/tags"),
+                additional_data: vec![String::from("eng"), String::from("Review")],
+            })
+        );
+        assert_eq!(link.unwrap().to_bytes(4), Ok(bytes));
+    }
+
+    #[test]
+    fn v23_link() {
+        let bytes = b"PIChttp://example.com/cover.png\x00".to_vec();
+        let link = LinkedInformation::parse(&bytes, 3).unwrap();
+        assert_eq!(link.frame_id, b"PIC");
+        assert_eq!(link.url, "http://example.com/cover.png");
+        assert_eq!(link.to_bytes(3), Ok(bytes));
+        assert_eq!(
+            link.to_bytes(4),
+            Err(LinkedInformationError::WrongFrameIDLength(
+                String::from("PIC"),
+                4
+            ))
+        );
+    }
+}
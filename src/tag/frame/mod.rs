@@ -1,21 +1,61 @@
-use std::{io::Read, rc::Rc};
+use std::{
+    io::Read,
+    sync::{Arc, OnceLock},
+};
 
 use thiserror::Error;
 
-use crate::utils::read_syncsafe_integer;
+use crate::utils::{read_syncsafe_integer, write_syncsafe_integer};
 
 use self::{frame_id::FrameID, frame_type::FrameType};
 
-mod frame_id;
-mod frame_type;
+pub mod attached_picture;
+pub mod audio_encryption;
+pub mod audio_seek_point_index;
+pub mod comment;
+pub mod commercial;
+pub mod equalisation;
+pub mod event_timing_codes;
+pub mod frame_id;
+pub mod frame_reader;
+pub mod frame_ref;
+pub mod frame_type;
+pub mod linked_information;
+pub mod mpeg_location_lookup_table;
+pub mod multiplicity;
+pub mod music_cd_identifier;
+pub mod position_synchronisation;
+pub mod recommended_buffer_size;
+pub mod registration;
+pub mod reverb;
+pub mod seek;
+pub mod signature;
+pub mod synchronised_tempo_codes;
+pub mod terms_of_use;
+pub mod text_information;
+pub mod text_values;
+pub mod timestamp_format;
+pub mod unique_file_identifier;
+
+/// The frame types decoded so far, for ID3v2.3 and ID3v2.4, which lay the flag data out
+/// differently.
+#[derive(Clone, Debug, Default)]
+struct DecodedFrameType([OnceLock<Result<FrameType, FrameReadError>>; 2]);
+
+impl PartialEq for DecodedFrameType {
+    /// Decoding doesn't change the frame, so the cache is left out of comparisons
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
 
 #[derive(PartialEq, Clone, Debug, Default)]
 pub struct Frame {
-    frame_type: FrameType,
+    decoded: DecodedFrameType,
     frame_id: FrameID,
     frame_size: u32,
     flags_byte: (u8, u8),
-    data: Rc<Vec<u8>>,
+    data: Arc<Vec<u8>>,
 }
 
 #[derive(PartialEq, Clone, Debug, Error)]
@@ -29,8 +69,102 @@ pub enum FrameReadError {
 }
 
 impl Frame {
+    /// Creates a frame with no flags set.
+    pub fn new(frame_id: FrameID, data: Vec<u8>) -> Frame {
+        Frame {
+            decoded: DecodedFrameType::default(),
+            frame_id,
+            frame_size: data.len() as u32,
+            flags_byte: (0, 0),
+            data: Arc::new(data),
+        }
+    }
+
     pub fn bytes_size(&self) -> u32 {
-        return self.frame_size + 10;
+        self.frame_size + 10
+    }
+
+    pub fn frame_id(&self) -> &FrameID {
+        &self.frame_id
+    }
+
+    /// The frame content, including any data appended because of the frame flags.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn flags(&self, major_version: u8) -> FrameFlags {
+        FrameFlags::from_bytes(self.flags_byte, major_version)
+    }
+
+    /// Reads the information appended to the frame header because of the grouping, encryption,
+    /// compression and data length indicator flags.
+    pub fn flag_data(&self, major_version: u8) -> Result<FrameFlagData, FrameReadError> {
+        FrameFlagData::read(&self.data, &self.flags(major_version), major_version)
+    }
+
+    /// Decodes the frame content into the type matching the frame id.
+    pub fn frame_type(&self, major_version: u8) -> Result<FrameType, FrameReadError> {
+        Ok(FrameType::parse(
+            &self.frame_id,
+            self.content(major_version)?,
+            major_version,
+        ))
+    }
+
+    /// Same as [`Frame::frame_type`], but the frame is only decoded the first time. The data
+    /// stays as it was read, so the frame is written back unchanged.
+    pub fn decoded(&self, major_version: u8) -> Result<&FrameType, FrameReadError> {
+        let cache = &self.decoded.0[usize::from(major_version != 3)];
+        cache
+            .get_or_init(|| self.frame_type(major_version))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// The frame content, without the data appended because of the frame flags.
+    pub fn content(&self, major_version: u8) -> Result<&[u8], FrameReadError> {
+        let flag_data = self.flag_data(major_version)?;
+        Ok(&self.data[flag_data.size..])
+    }
+
+    /// Makes a copy of this frame with its data replaced, keeping the flags.
+    pub fn with_data(&self, data: Vec<u8>) -> Frame {
+        Frame {
+            decoded: DecodedFrameType::default(),
+            frame_id: self.frame_id.clone(),
+            frame_size: data.len() as u32,
+            flags_byte: self.flags_byte,
+            data: Arc::new(data),
+        }
+    }
+
+    /// Makes a copy of this frame with its content replaced by the given plaintext. The
+    /// encryption flag and method symbol are removed, everything else is kept.
+    pub fn with_decrypted_content(
+        &self,
+        major_version: u8,
+        plaintext: &[u8],
+    ) -> Result<Frame, FrameReadError> {
+        let mut flag_data = self.flag_data(major_version)?;
+        flag_data.encryption_method = None;
+
+        let mut data = flag_data.to_bytes(major_version);
+        data.extend_from_slice(plaintext);
+
+        let encryption_bit = if major_version == 3 {
+            0b01000000
+        } else {
+            0b00000100
+        };
+
+        Ok(Frame {
+            decoded: DecodedFrameType::default(),
+            frame_id: self.frame_id.clone(),
+            frame_size: data.len() as u32,
+            flags_byte: (self.flags_byte.0, self.flags_byte.1 & !encryption_bit),
+            data: Arc::new(data),
+        })
     }
 
     fn read_id(reader: &mut impl Read) -> Result<FrameID, FrameReadError> {
@@ -40,37 +174,213 @@ impl Frame {
             .read_exact(&mut frame_id)
             .map_err(|_| FrameReadError::NotEnoughBytes)?;
 
-        FrameID::try_from(frame_id).map_err(|err| FrameReadError::InvalidFrameID(err))
+        FrameID::try_from(frame_id).map_err(FrameReadError::InvalidFrameID)
     }
 
-    pub fn read(reader: &mut impl Read) -> Result<Frame, FrameReadError> {
-        let frame_id = Frame::read_id(reader)?;
-
-        let mut buffer = [0u8; 6];
+    /// The frame header followed by its data, as it is stored in a tag.
+    pub fn to_bytes(&self, major_version: u8) -> Vec<u8> {
+        let size = self.data.len() as u32;
+        let mut bytes = Vec::with_capacity(10 + self.data.len());
+        bytes.extend_from_slice(self.frame_id.bytes());
+        if major_version == 3 {
+            bytes.extend_from_slice(&size.to_be_bytes());
+        } else {
+            bytes.extend_from_slice(&write_syncsafe_integer(size));
+        }
+        bytes.extend_from_slice(&[self.flags_byte.0, self.flags_byte.1]);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
 
-        reader
-            .read_exact(&mut buffer)
-            .map_err(|_| FrameReadError::NotEnoughBytes)?;
+    /// Reads a frame of an ID3v2.4 tag.
+    pub fn read(reader: &mut impl Read) -> Result<Frame, FrameReadError> {
+        Frame::read_for_version(reader, 4)
+    }
 
-        let frame_size = read_syncsafe_integer([buffer[0], buffer[1], buffer[2], buffer[3]]);
-        let flags_byte = (buffer[4], buffer[5]);
+    /// Reads a frame of a tag with the given major version. Sizes are only syncsafe since
+    /// ID3v2.4.
+    pub fn read_for_version(
+        reader: &mut impl Read,
+        major_version: u8,
+    ) -> Result<Frame, FrameReadError> {
+        let (frame_id, frame_size, flags_byte) = Frame::read_header(reader, major_version)?;
 
-        let mut data = Vec::new();
-        data.resize(frame_size as usize, 0);
+        let mut data = vec![0; frame_size as usize];
 
         reader
             .read_exact(&mut data[..])
             .map_err(|_| FrameReadError::NotEnoughBytes)?;
 
         Ok(Frame {
-            frame_type: FrameType::Unknown,
+            decoded: DecodedFrameType::default(),
             frame_id,
             frame_size,
             flags_byte,
-            data: Rc::new(data),
+            data: Arc::new(data),
         })
     }
+
+    /// Reads the id, size and flags of the 10 bytes frame header.
+    fn read_header(
+        reader: &mut impl Read,
+        major_version: u8,
+    ) -> Result<(FrameID, u32, (u8, u8)), FrameReadError> {
+        let frame_id = Frame::read_id(reader)?;
+
+        let mut buffer = [0u8; 6];
+
+        reader
+            .read_exact(&mut buffer)
+            .map_err(|_| FrameReadError::NotEnoughBytes)?;
+
+        let size_bytes = [buffer[0], buffer[1], buffer[2], buffer[3]];
+        let frame_size = if major_version == 3 {
+            u32::from_be_bytes(size_bytes)
+        } else {
+            read_syncsafe_integer(size_bytes)
+        };
+        let flags_byte = (buffer[4], buffer[5]);
+
+        Ok((frame_id, frame_size, flags_byte))
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct FrameFlags {
+    pub tag_alter_preservation: bool,
+    pub file_alter_preservation: bool,
+    pub read_only: bool,
+    pub grouping_identity: bool,
+    pub compression: bool,
+    pub encryption: bool,
+    /// Only present on ID3v2.4
+    pub unsynchronisation: bool,
+    /// Only present on ID3v2.4
+    pub data_length_indicator: bool,
+}
+
+impl FrameFlags {
+    pub fn from_bytes((status, format): (u8, u8), major_version: u8) -> Self {
+        if major_version == 3 {
+            FrameFlags {
+                tag_alter_preservation: status & 0b10000000 != 0,
+                file_alter_preservation: status & 0b01000000 != 0,
+                read_only: status & 0b00100000 != 0,
+                compression: format & 0b10000000 != 0,
+                encryption: format & 0b01000000 != 0,
+                grouping_identity: format & 0b00100000 != 0,
+                unsynchronisation: false,
+                data_length_indicator: false,
+            }
+        } else {
+            FrameFlags {
+                tag_alter_preservation: status & 0b01000000 != 0,
+                file_alter_preservation: status & 0b00100000 != 0,
+                read_only: status & 0b00010000 != 0,
+                grouping_identity: format & 0b01000000 != 0,
+                compression: format & 0b00001000 != 0,
+                encryption: format & 0b00000100 != 0,
+                unsynchronisation: format & 0b00000010 != 0,
+                data_length_indicator: format & 0b00000001 != 0,
+            }
+        }
+    }
 }
 
+/// Information appended to the frame header because of its flags. It is stored at the start of
+/// the frame data, in a version dependent order.
 #[derive(PartialEq, Clone, Debug, Default)]
-pub struct FrameFlags {}
+pub struct FrameFlagData {
+    pub group_symbol: Option<u8>,
+    pub encryption_method: Option<u8>,
+    /// Decompressed size on ID3v2.3, data length indicator on ID3v2.4
+    pub data_length: Option<u32>,
+    /// in bytes
+    pub size: usize,
+}
+
+impl FrameFlagData {
+    /// Reads the flag data at the start of the frame data.
+    pub fn read(
+        frame_data: &[u8],
+        flags: &FrameFlags,
+        major_version: u8,
+    ) -> Result<FrameFlagData, FrameReadError> {
+        let mut flag_data = FrameFlagData::default();
+        let mut data = frame_data;
+
+        let read_byte = |data: &mut &[u8]| {
+            let (byte, rest) = data.split_first().ok_or(FrameReadError::NotEnoughBytes)?;
+            *data = rest;
+            Ok(*byte)
+        };
+        let read_size = |data: &mut &[u8]| {
+            let (size, rest) = data
+                .split_first_chunk::<4>()
+                .ok_or(FrameReadError::NotEnoughBytes)?;
+            *data = rest;
+            Ok(*size)
+        };
+
+        if major_version == 3 {
+            if flags.compression {
+                flag_data.data_length = Some(u32::from_be_bytes(read_size(&mut data)?));
+            }
+            if flags.encryption {
+                flag_data.encryption_method = Some(read_byte(&mut data)?);
+            }
+            if flags.grouping_identity {
+                flag_data.group_symbol = Some(read_byte(&mut data)?);
+            }
+        } else {
+            if flags.grouping_identity {
+                flag_data.group_symbol = Some(read_byte(&mut data)?);
+            }
+            if flags.encryption {
+                flag_data.encryption_method = Some(read_byte(&mut data)?);
+            }
+            if flags.data_length_indicator {
+                flag_data.data_length = Some(read_syncsafe_integer(read_size(&mut data)?));
+            }
+        }
+
+        flag_data.size = frame_data.len() - data.len();
+        Ok(flag_data)
+    }
+
+    pub fn to_bytes(&self, major_version: u8) -> Vec<u8> {
+        let mut bytes = vec![];
+        if major_version == 3 {
+            if let Some(data_length) = self.data_length {
+                bytes.extend_from_slice(&data_length.to_be_bytes());
+            }
+            bytes.extend(self.encryption_method);
+            bytes.extend(self.group_symbol);
+        } else {
+            bytes.extend(self.group_symbol);
+            bytes.extend(self.encryption_method);
+            if let Some(data_length) = self.data_length {
+                bytes.extend_from_slice(&write_syncsafe_integer(data_length));
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn decoded_once() {
+        let bytes = b"TIT2\x00\x00\x00\x06\x00\x00\x00Title".to_vec();
+        let frame = Frame::read(&mut &bytes[..]).unwrap();
+
+        let decoded = frame.decoded(4).unwrap();
+        assert!(matches!(decoded, FrameType::TextInformation(Ok(_))));
+        assert!(std::ptr::eq(decoded, frame.decoded(4).unwrap()));
+        assert_eq!(frame, Frame::read(&mut &bytes[..]).unwrap());
+        assert_eq!(frame.to_bytes(4), bytes);
+    }
+}
@@ -0,0 +1,370 @@
+use thiserror::Error;
+
+use crate::{
+    mpeg::MpegFrame,
+    utils::{BitReader, BitWriter},
+};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MpegLocationReference {
+    /// How many bytes this reference is past the nominal `bytes_between_reference`
+    pub bytes_deviation: u32,
+    /// How many milliseconds this reference is past the nominal `milliseconds_between_reference`
+    pub milliseconds_deviation: u32,
+}
+
+/// An absolute position, computed from the MLLT references.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MpegLocation {
+    pub frame: u64,
+    /// Relative to the first audio frame after the tag
+    pub byte_offset: u64,
+    pub milliseconds: u64,
+}
+
+/// Content of an MLLT frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MpegLocationLookupTable {
+    pub frames_between_reference: u16,
+    /// 24 bits at most
+    pub bytes_between_reference: u32,
+    /// 24 bits at most
+    pub milliseconds_between_reference: u32,
+    pub bits_for_bytes_deviation: u8,
+    pub bits_for_milliseconds_deviation: u8,
+    pub references: Vec<MpegLocationReference>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum MpegLocationLookupTableError {
+    #[error("Could not read enough bytes to parse the 10 bytes of the table header")]
+    MissingData,
+    #[error("Deviations of {0} bits are not supported. The maximum is 32")]
+    UnsupportedDeviationWidth(u8),
+    #[error("The sum of the deviation bits, {0}, must be a multiple of 4")]
+    InvalidDeviationWidth(u16),
+    #[error("The value {0} does not fit in the 24 bits reserved for it")]
+    ValueTooLarge(u64),
+    #[error("The deviation {0} does not fit in {1} bits")]
+    DeviationTooLarge(u32, u8),
+    #[error("At least 2 MPEG frames are needed to generate a lookup table")]
+    NotEnoughFrames,
+    #[error("The number of frames between references must not be 0")]
+    ZeroFramesBetweenReference,
+}
+
+const MAX_24_BITS: u32 = 0xFF_FF_FF;
+
+/// Widens the deviations until they add up to a multiple of 4 bits, without making either of
+/// them wider than 32 bits.
+fn pad_deviation_widths(mut bytes_bits: u8, mut milliseconds_bits: u8) -> (u8, u8) {
+    while !(bytes_bits + milliseconds_bits).is_multiple_of(4) {
+        if milliseconds_bits < 32 {
+            milliseconds_bits += 1;
+        } else {
+            bytes_bits += 1;
+        }
+    }
+    (bytes_bits, milliseconds_bits)
+}
+
+impl MpegLocationLookupTable {
+    pub fn parse(bytes: &[u8]) -> Result<Self, MpegLocationLookupTableError> {
+        let [f1, f2, b1, b2, b3, m1, m2, m3, bits_for_bytes_deviation, bits_for_milliseconds_deviation, table @ ..] =
+            bytes
+        else {
+            return Err(MpegLocationLookupTableError::MissingData);
+        };
+        let bits_for_bytes_deviation = *bits_for_bytes_deviation;
+        let bits_for_milliseconds_deviation = *bits_for_milliseconds_deviation;
+
+        for bits in [bits_for_bytes_deviation, bits_for_milliseconds_deviation] {
+            if bits > 32 {
+                return Err(MpegLocationLookupTableError::UnsupportedDeviationWidth(
+                    bits,
+                ));
+            }
+        }
+
+        let mut references = vec![];
+        let reference_bits =
+            bits_for_bytes_deviation as usize + bits_for_milliseconds_deviation as usize;
+        if reference_bits != 0 {
+            let mut reader = BitReader::new(table);
+            // Anything shorter than a whole reference is padding
+            while reader.remaining_bits() >= reference_bits {
+                references.push(MpegLocationReference {
+                    bytes_deviation: reader.read_bits(bits_for_bytes_deviation).unwrap(),
+                    milliseconds_deviation: reader
+                        .read_bits(bits_for_milliseconds_deviation)
+                        .unwrap(),
+                });
+            }
+        }
+
+        Ok(MpegLocationLookupTable {
+            frames_between_reference: u16::from_be_bytes([*f1, *f2]),
+            bytes_between_reference: u32::from_be_bytes([0, *b1, *b2, *b3]),
+            milliseconds_between_reference: u32::from_be_bytes([0, *m1, *m2, *m3]),
+            bits_for_bytes_deviation,
+            bits_for_milliseconds_deviation,
+            references,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MpegLocationLookupTableError> {
+        let deviation_bits =
+            self.bits_for_bytes_deviation as u16 + self.bits_for_milliseconds_deviation as u16;
+        if !deviation_bits.is_multiple_of(4) {
+            return Err(MpegLocationLookupTableError::InvalidDeviationWidth(
+                deviation_bits,
+            ));
+        }
+        for bits in [
+            self.bits_for_bytes_deviation,
+            self.bits_for_milliseconds_deviation,
+        ] {
+            if bits > 32 {
+                return Err(MpegLocationLookupTableError::UnsupportedDeviationWidth(
+                    bits,
+                ));
+            }
+        }
+        for value in [
+            self.bytes_between_reference,
+            self.milliseconds_between_reference,
+        ] {
+            if value > MAX_24_BITS {
+                return Err(MpegLocationLookupTableError::ValueTooLarge(value as u64));
+            }
+        }
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&self.frames_between_reference.to_be_bytes());
+        bytes.extend_from_slice(&self.bytes_between_reference.to_be_bytes()[1..]);
+        bytes.extend_from_slice(&self.milliseconds_between_reference.to_be_bytes()[1..]);
+        bytes.push(self.bits_for_bytes_deviation);
+        bytes.push(self.bits_for_milliseconds_deviation);
+
+        let mut writer = BitWriter::new();
+        for reference in &self.references {
+            for (value, bits) in [
+                (reference.bytes_deviation, self.bits_for_bytes_deviation),
+                (
+                    reference.milliseconds_deviation,
+                    self.bits_for_milliseconds_deviation,
+                ),
+            ] {
+                if bits < 32 && value >> bits != 0 {
+                    return Err(MpegLocationLookupTableError::DeviationTooLarge(value, bits));
+                }
+                writer.write_bits(value, bits);
+            }
+        }
+        bytes.extend(writer.into_bytes());
+
+        Ok(bytes)
+    }
+
+    /// Absolute positions of every reference, starting with the first audio frame at position 0.
+    pub fn locations(&self) -> Vec<MpegLocation> {
+        let mut location = MpegLocation {
+            frame: 0,
+            byte_offset: 0,
+            milliseconds: 0,
+        };
+        let mut locations = vec![location.clone()];
+        for reference in &self.references {
+            location.frame += self.frames_between_reference as u64;
+            location.byte_offset +=
+                self.bytes_between_reference as u64 + reference.bytes_deviation as u64;
+            location.milliseconds += self.milliseconds_between_reference as u64
+                + reference.milliseconds_deviation as u64;
+            locations.push(location.clone());
+        }
+        locations
+    }
+
+    /// The last known location at or before the given time. Seeking there and decoding forward
+    /// is enough to reach any point in a VBR stream.
+    pub fn location_before(&self, milliseconds: u64) -> MpegLocation {
+        self.locations()
+            .into_iter()
+            .take_while(|location| location.milliseconds <= milliseconds)
+            .last()
+            .unwrap()
+    }
+
+    /// Builds a lookup table from a scan of the audio frames, placing one reference every
+    /// `frames_between_reference` frames. See [`crate::mpeg::scan_frames`].
+    pub fn generate(
+        frames: &[MpegFrame],
+        frames_between_reference: u16,
+    ) -> Result<Self, MpegLocationLookupTableError> {
+        if frames_between_reference == 0 {
+            return Err(MpegLocationLookupTableError::ZeroFramesBetweenReference);
+        }
+        if frames.len() < 2 {
+            return Err(MpegLocationLookupTableError::NotEnoughFrames);
+        }
+
+        let first_offset = frames[0].offset;
+        let mut elapsed_milliseconds = 0f64;
+        let mut points = vec![];
+        for (index, frame) in frames.iter().enumerate() {
+            if index % frames_between_reference as usize == 0 {
+                points.push((
+                    frame.offset - first_offset,
+                    elapsed_milliseconds.round() as u64,
+                ));
+            }
+            elapsed_milliseconds += frame.duration();
+        }
+
+        let steps = points
+            .windows(2)
+            .map(|pair| (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1))
+            .collect::<Vec<(u64, u64)>>();
+
+        // Deviations are unsigned, so the nominal distance must be the smallest one
+        let bytes_between_reference = steps.iter().map(|step| step.0).min().unwrap_or(0);
+        let milliseconds_between_reference = steps.iter().map(|step| step.1).min().unwrap_or(0);
+        for value in [bytes_between_reference, milliseconds_between_reference] {
+            if value > MAX_24_BITS as u64 {
+                return Err(MpegLocationLookupTableError::ValueTooLarge(value));
+            }
+        }
+
+        let references = steps
+            .iter()
+            .map(|(bytes, milliseconds)| {
+                let deviation = |value: u64| {
+                    u32::try_from(value)
+                        .map_err(|_| MpegLocationLookupTableError::ValueTooLarge(value))
+                };
+                Ok(MpegLocationReference {
+                    bytes_deviation: deviation(bytes - bytes_between_reference)?,
+                    milliseconds_deviation: deviation(
+                        milliseconds - milliseconds_between_reference,
+                    )?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bits_needed = |value: u32| (u32::BITS - value.leading_zeros()) as u8;
+        let (bits_for_bytes_deviation, bits_for_milliseconds_deviation) = pad_deviation_widths(
+            references
+                .iter()
+                .map(|reference| bits_needed(reference.bytes_deviation))
+                .max()
+                .unwrap_or(0),
+            references
+                .iter()
+                .map(|reference| bits_needed(reference.milliseconds_deviation))
+                .max()
+                .unwrap_or(0),
+        );
+
+        Ok(MpegLocationLookupTable {
+            frames_between_reference,
+            bytes_between_reference: bytes_between_reference as u32,
+            milliseconds_between_reference: milliseconds_between_reference as u32,
+            bits_for_bytes_deviation,
+            bits_for_milliseconds_deviation,
+            references,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpeg::MpegFrameHeader;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bit_packed_references() {
+        let bytes = [
+            0,
+            2,
+            0,
+            0x03,
+            0x40,
+            0,
+            0,
+            52,
+            4,
+            4, //
+            0b0001_0000,
+            0b1111_0011,
+        ];
+        let mllt = MpegLocationLookupTable::parse(&bytes);
+        assert_eq!(
+            mllt,
+            Ok(MpegLocationLookupTable {
+                frames_between_reference: 2,
+                bytes_between_reference: 832,
+                milliseconds_between_reference: 52,
+                bits_for_bytes_deviation: 4,
+                bits_for_milliseconds_deviation: 4,
+                references: vec![
+                    MpegLocationReference {
+                        bytes_deviation: 1,
+                        milliseconds_deviation: 0
+                    },
+                    MpegLocationReference {
+                        bytes_deviation: 15,
+                        milliseconds_deviation: 3
+                    },
+                ]
+            })
+        );
+        assert_eq!(mllt.unwrap().to_bytes(), Ok(bytes.to_vec()));
+    }
+
+    #[test]
+    fn generate_from_vbr_frames() {
+        let header = |first_byte: u8| MpegFrameHeader::parse([0xFF, 0xFB, first_byte, 0]).unwrap();
+        // 128, 128, 320 and 128 kbit/s frames
+        let headers = [header(0x90), header(0x90), header(0xE0), header(0x90)];
+        let mut offset = 0;
+        let frames = headers
+            .into_iter()
+            .map(|header| {
+                let frame = MpegFrame { offset, header };
+                offset += frame.header.frame_length() as u64;
+                frame
+            })
+            .collect::<Vec<MpegFrame>>();
+
+        let mllt = MpegLocationLookupTable::generate(&frames, 1).unwrap();
+        assert_eq!(mllt.bytes_between_reference, 417);
+        assert_eq!(mllt.bits_for_bytes_deviation, 10);
+        assert_eq!(mllt.bits_for_milliseconds_deviation, 2);
+        let locations = mllt.locations();
+        assert_eq!(locations[3].byte_offset, frames[3].offset);
+        assert_eq!(locations[3].milliseconds, 78);
+        assert_eq!(mllt.location_before(60).frame, 2);
+
+        let bytes = mllt.to_bytes().unwrap();
+        assert_eq!(MpegLocationLookupTable::parse(&bytes), Ok(mllt));
+
+        // The second reference is more than 4 GiB past the first
+        let frames = [0, 1, 1 << 33].map(|offset| MpegFrame {
+            offset,
+            header: header(0x90),
+        });
+        assert_eq!(
+            MpegLocationLookupTable::generate(&frames, 1),
+            Err(MpegLocationLookupTableError::ValueTooLarge((1 << 33) - 2))
+        );
+    }
+
+    #[test]
+    fn padded_deviation_widths() {
+        assert_eq!(pad_deviation_widths(10, 1), (10, 2));
+        assert_eq!(pad_deviation_widths(31, 32), (32, 32));
+        assert_eq!(pad_deviation_widths(32, 0), (32, 0));
+        assert_eq!(pad_deviation_widths(3, 32), (4, 32));
+    }
+}
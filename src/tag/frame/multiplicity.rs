@@ -0,0 +1,201 @@
+use crate::tag::encoding::Encoding;
+
+use super::frame_id::FrameID;
+
+/// How many frames with the same id a tag may hold, according to the spec.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum FrameMultiplicity {
+    OnePerTag,
+    /// Several frames, but none with the same value for one of the keys, as the language and
+    /// description of a COMM frame, or either the owner or the symbol of an ENCR frame. Keys
+    /// are compared in order, and a frame lacking a key doesn't conflict on it.
+    OnePerKeys(Vec<Vec<u8>>),
+    Unrestricted,
+}
+
+/// Bytes up to the first NUL, as the owner identifier of a UFID frame.
+fn until_nul(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .split(|byte| *byte == 0)
+        .next()
+        .unwrap_or(bytes)
+        .to_vec()
+}
+
+fn split_encoding(bytes: &[u8]) -> Option<(Encoding, &[u8])> {
+    let (encoding_byte, rest) = bytes.split_first()?;
+    Some((Encoding::from_encoding_byte(*encoding_byte).ok()?, rest))
+}
+
+/// The description of TXXX and WXXX frames, which comes right after the encoding.
+fn description(bytes: &[u8]) -> Option<Vec<u8>> {
+    let (encoding, rest) = split_encoding(bytes)?;
+    Some(encoding.read_string(rest).0.into_bytes())
+}
+
+/// The language followed by the description of COMM, USLT and SYLT frames. SYLT frames have 2
+/// more bytes between them.
+fn language_and_description(bytes: &[u8], skipped: usize) -> Option<Vec<u8>> {
+    let (encoding, rest) = split_encoding(bytes)?;
+    let language = rest.get(..3)?;
+    let (description, _) = encoding.read_string(rest.get(3 + skipped..)?);
+    Some([language, description.as_bytes()].concat())
+}
+
+/// The description of an APIC frame, which follows the MIME type and picture type. The file
+/// icons, of picture type 1 and 2, are also keyed by their type, as there may only be one of
+/// each.
+fn picture_keys(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let (encoding, rest) = split_encoding(bytes)?;
+    let (_, rest) = Encoding::Latin1.read_string(rest);
+    let (picture_type, rest) = rest.split_first()?;
+    let description = encoding.read_string(rest).0.into_bytes();
+    match picture_type {
+        1 | 2 => Some(vec![description, vec![*picture_type]]),
+        _ => Some(vec![description]),
+    }
+}
+
+/// The owner identifier and the symbol of ENCR and GRID frames, each unique in a tag.
+fn owner_and_symbol(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let (owner, rest) = Encoding::Latin1.read_string(bytes);
+    let symbol = rest.first()?;
+    Some(vec![owner.into_bytes(), vec![*symbol]])
+}
+
+/// The description of a GEOB frame, which follows the MIME type and filename.
+fn object_description(bytes: &[u8]) -> Option<Vec<u8>> {
+    let (encoding, rest) = split_encoding(bytes)?;
+    let (_, rest) = Encoding::Latin1.read_string(rest);
+    let (_, rest) = encoding.read_string(rest);
+    Some(encoding.read_string(rest).0.into_bytes())
+}
+
+impl FrameMultiplicity {
+    /// The rule for a frame with the given id and content. Frames the spec doesn't restrict, and
+    /// experimental ones, may be repeated freely.
+    pub fn of(frame_id: &FrameID, content: &[u8]) -> Self {
+        let one = |key: Option<Vec<u8>>| key.map(|key| vec![key]);
+        let keys = match frame_id.bytes() {
+            b"TXXX" | b"WXXX" => one(description(content)),
+            b"COMM" | b"USLT" => one(language_and_description(content, 0)),
+            b"SYLT" => one(language_and_description(content, 2)),
+            b"APIC" => picture_keys(content),
+            b"GEOB" => one(object_description(content)),
+            b"UFID" | b"AENC" | b"POPM" | b"RVA2" => one(Some(until_nul(content))),
+            b"EQU2" => one(Some(until_nul(content.get(1..).unwrap_or_default()))),
+            b"USER" => one(content.get(1..4).map(<[u8]>::to_vec)),
+            b"ENCR" | b"GRID" => owner_and_symbol(content),
+            b"PRIV" | b"LINK" | b"SIGN" | b"COMR" | b"WCOM" | b"WOAR" => {
+                one(Some(content.to_vec()))
+            }
+            b"MCDI" | b"ETCO" | b"MLLT" | b"SYTC" | b"RVRB" | b"PCNT" | b"RBUF" | b"POSS"
+            | b"OWNE" | b"SEEK" | b"ASPI" | b"EQUA" | b"RVAD" | b"IPLS" => {
+                return FrameMultiplicity::OnePerTag
+            }
+            [b'T' | b'W', ..] => return FrameMultiplicity::OnePerTag,
+            _ => return FrameMultiplicity::Unrestricted,
+        };
+        // Malformed frames are only considered the same when their content is identical
+        FrameMultiplicity::OnePerKeys(keys.unwrap_or_else(|| vec![content.to_vec()]))
+    }
+
+    /// Whether two frames with the same id, and these rules, can't be in the same tag.
+    pub fn conflicts_with(&self, other: &FrameMultiplicity) -> bool {
+        match self {
+            FrameMultiplicity::OnePerTag => true,
+            FrameMultiplicity::OnePerKeys(keys) => match other {
+                FrameMultiplicity::OnePerKeys(other_keys) => keys
+                    .iter()
+                    .zip(other_keys)
+                    .any(|(key, other_key)| key == other_key),
+                _ => other.conflicts_with(self),
+            },
+            FrameMultiplicity::Unrestricted => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn multiplicity(id: &[u8; 4], content: &[u8]) -> FrameMultiplicity {
+        FrameMultiplicity::of(&FrameID::try_from(*id).unwrap(), content)
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(
+            multiplicity(b"TIT2", b"\x00Title"),
+            FrameMultiplicity::OnePerTag
+        );
+        assert_eq!(
+            multiplicity(b"TXXX", b"\x03Mood\x00Calm"),
+            FrameMultiplicity::OnePerKeys(vec![b"Mood".to_vec()])
+        );
+        assert_eq!(
+            multiplicity(b"COMM", b"\x00engSource\x00Vinyl"),
+            multiplicity(b"COMM", b"\x03engSource\x00CD")
+        );
+        assert_ne!(
+            multiplicity(b"COMM", b"\x00engSource\x00Vinyl"),
+            multiplicity(b"COMM", b"\x00fraSource\x00Vinyl")
+        );
+        assert_eq!(
+            multiplicity(b"APIC", b"\x00image/png\x00\x03Cover\x00data"),
+            FrameMultiplicity::OnePerKeys(vec![b"Cover".to_vec()])
+        );
+        assert_eq!(
+            multiplicity(b"GEOB", b"\x00text/plain\x00notes.txt\x00Notes\x00data"),
+            FrameMultiplicity::OnePerKeys(vec![b"Notes".to_vec()])
+        );
+        assert_eq!(
+            multiplicity(b"XABC", b"data"),
+            FrameMultiplicity::Unrestricted
+        );
+    }
+
+    #[test]
+    fn owner_and_symbol_keys() {
+        let conflicts = |content: &[u8], other: &[u8]| {
+            multiplicity(b"ENCR", content).conflicts_with(&multiplicity(b"ENCR", other))
+        };
+        assert!(conflicts(
+            b"drm@example.com\x00\x81",
+            b"drm@example.com\x00\x82"
+        ));
+        assert!(conflicts(
+            b"drm@example.com\x00\x81",
+            b"other@example.com\x00\x81"
+        ));
+        assert!(!conflicts(
+            b"drm@example.com\x00\x81",
+            b"other@example.com\x00\x82"
+        ));
+    }
+
+    #[test]
+    fn one_file_icon_per_tag() {
+        let conflicts = |content: &[u8], other: &[u8]| {
+            multiplicity(b"APIC", content).conflicts_with(&multiplicity(b"APIC", other))
+        };
+        assert!(conflicts(
+            b"\x00image/png\x00\x01Icon\x00data",
+            b"\x00image/png\x00\x01Other icon\x00data"
+        ));
+        assert!(!conflicts(
+            b"\x00image/png\x00\x01Icon\x00data",
+            b"\x00image/png\x00\x02Other icon\x00data"
+        ));
+        assert!(!conflicts(
+            b"\x00image/png\x00\x03Front\x00data",
+            b"\x00image/png\x00\x03Back\x00data"
+        ));
+        assert!(conflicts(
+            b"\x00image/png\x00\x01Cover\x00data",
+            b"\x00image/png\x00\x03Cover\x00data"
+        ));
+    }
+}
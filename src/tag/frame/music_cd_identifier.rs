@@ -0,0 +1,286 @@
+use sha1_smol::Sha1;
+use thiserror::Error;
+
+/// Track number the table of contents gives to the lead-out area.
+pub const LEAD_OUT_TRACK_NUMBER: u8 = 0xAA;
+/// Sectors of the pregap, which come before the first track but aren't counted by LBAs.
+pub const PREGAP_SECTORS: u32 = 150;
+const SECTORS_PER_SECOND: u32 = 75;
+/// ADR 1 with the control bits of an audio track without pre-emphasis
+const AUDIO_TRACK_ADR_CONTROL: u8 = 0x10;
+const MUSICBRAINZ_BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789._";
+
+/// A track descriptor of the table of contents.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TocTrack {
+    pub number: u8,
+    /// ADR in the high nibble and control bits in the low nibble
+    pub adr_control: u8,
+    /// Logical block address of the track start, in sectors
+    pub lba: u32,
+}
+
+impl TocTrack {
+    /// in sectors, counting the pregap, as used by disc ids
+    pub fn offset(&self) -> u32 {
+        self.lba + PREGAP_SECTORS
+    }
+}
+
+/// Content of an MCDI frame: the table of contents of the CD the audio was taken from, as
+/// returned by the READ TOC command.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MusicCdIdentifier {
+    pub first_track: u8,
+    pub last_track: u8,
+    pub tracks: Vec<TocTrack>,
+    pub lead_out: TocTrack,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum MusicCdIdentifierError {
+    #[error("Could not read enough bytes to parse the table of contents header")]
+    MissingData,
+    #[error("The table of contents should be {0} bytes long, but it was {1}")]
+    InvalidLength(usize, usize),
+    #[error("The table of contents has no lead-out track descriptor")]
+    MissingLeadOut,
+    #[error("The table of contents has no tracks")]
+    NoTracks,
+    #[error("A CD holds at most 99 tracks, but there are {0}")]
+    TooManyTracks(usize),
+    #[error("The track starting at sector {0} does not start after the previous one")]
+    UnorderedOffsets(u32),
+}
+
+impl MusicCdIdentifier {
+    pub fn parse(bytes: &[u8]) -> Result<Self, MusicCdIdentifierError> {
+        let [l1, l2, first_track, last_track, descriptors @ ..] = bytes else {
+            return Err(MusicCdIdentifierError::MissingData);
+        };
+        // The data length doesn't count its own 2 bytes
+        let length = u16::from_be_bytes([*l1, *l2]) as usize + 2;
+        if length != bytes.len() || !(length - 4).is_multiple_of(8) {
+            return Err(MusicCdIdentifierError::InvalidLength(length, bytes.len()));
+        }
+
+        let mut tracks = descriptors
+            .chunks_exact(8)
+            .map(|descriptor| TocTrack {
+                number: descriptor[2],
+                adr_control: descriptor[1],
+                lba: u32::from_be_bytes([
+                    descriptor[4],
+                    descriptor[5],
+                    descriptor[6],
+                    descriptor[7],
+                ]),
+            })
+            .collect::<Vec<TocTrack>>();
+        let Some(lead_out_position) = tracks
+            .iter()
+            .position(|track| track.number == LEAD_OUT_TRACK_NUMBER)
+        else {
+            return Err(MusicCdIdentifierError::MissingLeadOut);
+        };
+        let lead_out = tracks.remove(lead_out_position);
+        if tracks.is_empty() {
+            return Err(MusicCdIdentifierError::NoTracks);
+        }
+
+        Ok(MusicCdIdentifier {
+            first_track: *first_track,
+            last_track: *last_track,
+            tracks,
+            lead_out,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MusicCdIdentifierError> {
+        if self.tracks.len() > 99 {
+            return Err(MusicCdIdentifierError::TooManyTracks(self.tracks.len()));
+        }
+        let length = 2 + 8 * (self.tracks.len() + 1) as u16;
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&length.to_be_bytes());
+        bytes.push(self.first_track);
+        bytes.push(self.last_track);
+        for track in self.tracks.iter().chain([&self.lead_out]) {
+            bytes.extend_from_slice(&[0, track.adr_control, track.number, 0]);
+            bytes.extend_from_slice(&track.lba.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    /// Builds the table of contents of an audio CD from the LBAs of its tracks, in order, and of
+    /// its lead-out.
+    pub fn from_track_offsets(
+        track_lbas: &[u32],
+        lead_out_lba: u32,
+    ) -> Result<Self, MusicCdIdentifierError> {
+        if track_lbas.is_empty() {
+            return Err(MusicCdIdentifierError::NoTracks);
+        }
+        if track_lbas.len() > 99 {
+            return Err(MusicCdIdentifierError::TooManyTracks(track_lbas.len()));
+        }
+        for pair in track_lbas.windows(2) {
+            if pair[1] <= pair[0] {
+                return Err(MusicCdIdentifierError::UnorderedOffsets(pair[1]));
+            }
+        }
+        if lead_out_lba <= track_lbas[track_lbas.len() - 1] {
+            return Err(MusicCdIdentifierError::UnorderedOffsets(lead_out_lba));
+        }
+
+        let tracks = track_lbas
+            .iter()
+            .enumerate()
+            .map(|(index, lba)| TocTrack {
+                number: index as u8 + 1,
+                adr_control: AUDIO_TRACK_ADR_CONTROL,
+                lba: *lba,
+            })
+            .collect::<Vec<TocTrack>>();
+
+        Ok(MusicCdIdentifier {
+            first_track: 1,
+            last_track: tracks.len() as u8,
+            tracks,
+            lead_out: TocTrack {
+                number: LEAD_OUT_TRACK_NUMBER,
+                adr_control: AUDIO_TRACK_ADR_CONTROL,
+                lba: lead_out_lba,
+            },
+        })
+    }
+
+    /// The disc id used by FreeDB and CDDB, usually written as 8 hexadecimal digits.
+    pub fn freedb_disc_id(&self) -> Result<u32, MusicCdIdentifierError> {
+        let Some(first_track) = self.tracks.first() else {
+            return Err(MusicCdIdentifierError::NoTracks);
+        };
+        let digit_sum = |mut seconds: u32| {
+            let mut sum = 0;
+            while seconds > 0 {
+                sum += seconds % 10;
+                seconds /= 10;
+            }
+            sum
+        };
+
+        let checksum = self
+            .tracks
+            .iter()
+            .map(|track| digit_sum(track.offset() / SECTORS_PER_SECOND))
+            .sum::<u32>();
+        let length = (self.lead_out.offset() / SECTORS_PER_SECOND)
+            .saturating_sub(first_track.offset() / SECTORS_PER_SECOND);
+
+        Ok((checksum % 0xFF) << 24 | length << 8 | self.tracks.len() as u32)
+    }
+
+    /// The disc id used by MusicBrainz: a SHA-1 of the track offsets, encoded in base 64 with an
+    /// alphabet safe for URLs.
+    pub fn musicbrainz_disc_id(&self) -> Result<String, MusicCdIdentifierError> {
+        if self.tracks.is_empty() {
+            return Err(MusicCdIdentifierError::NoTracks);
+        }
+        let mut toc = format!(
+            "{:02X}{:02X}{:08X}",
+            self.first_track,
+            self.last_track,
+            self.lead_out.offset()
+        );
+        for number in 1..=99 {
+            let offset = self
+                .tracks
+                .iter()
+                .find(|track| track.number == number)
+                .map(TocTrack::offset)
+                .unwrap_or(0);
+            toc.push_str(&format!("{offset:08X}"));
+        }
+
+        let digest = Sha1::from(toc).digest().bytes();
+        let mut disc_id = String::new();
+        for chunk in digest.chunks(3) {
+            let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, byte)| {
+                bits | (*byte as u32) << (16 - 8 * index)
+            });
+            for index in 0..4 {
+                if index <= chunk.len() {
+                    let sextet = (bits >> (18 - 6 * index)) & 0x3F;
+                    disc_id.push(MUSICBRAINZ_BASE64_ALPHABET[sextet as usize] as char);
+                } else {
+                    disc_id.push('-');
+                }
+            }
+        }
+        Ok(disc_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Offsets of the disc used by the libdiscid test suite
+    const OFFSETS: [u32; 22] = [
+        150, 9700, 25887, 39297, 53795, 63735, 77517, 94877, 107270, 123552, 135522, 148422,
+        161197, 174790, 192022, 205545, 218010, 228700, 239590, 255470, 266932, 288750,
+    ];
+    const LEAD_OUT_OFFSET: u32 = 303602;
+
+    fn identifier() -> MusicCdIdentifier {
+        let lbas = OFFSETS.map(|offset| offset - PREGAP_SECTORS);
+        MusicCdIdentifier::from_track_offsets(&lbas, LEAD_OUT_OFFSET - PREGAP_SECTORS).unwrap()
+    }
+
+    #[test]
+    fn disc_ids() {
+        let mcdi = identifier();
+        assert_eq!(
+            format!("{:08x}", mcdi.freedb_disc_id().unwrap()),
+            "370fce16"
+        );
+        assert_eq!(
+            mcdi.musicbrainz_disc_id().unwrap(),
+            "xUp1F2NkfP8s8jaeFn_Av3jNEI4-"
+        );
+
+        let no_tracks = MusicCdIdentifier {
+            tracks: vec![],
+            ..mcdi
+        };
+        assert_eq!(
+            no_tracks.freedb_disc_id(),
+            Err(MusicCdIdentifierError::NoTracks)
+        );
+        assert_eq!(
+            no_tracks.musicbrainz_disc_id(),
+            Err(MusicCdIdentifierError::NoTracks)
+        );
+    }
+
+    #[test]
+    fn toc_round_trip() {
+        let mcdi = identifier();
+        let bytes = mcdi.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 4 + 23 * 8);
+        assert_eq!(&bytes[..12], &[0, 186, 1, 22, 0, 0x10, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(MusicCdIdentifier::parse(&bytes), Ok(mcdi));
+    }
+
+    #[test]
+    fn missing_lead_out() {
+        let bytes = [0, 10, 1, 1, 0, 0x10, 1, 0, 0, 0, 0, 0];
+        assert_eq!(
+            MusicCdIdentifier::parse(&bytes),
+            Err(MusicCdIdentifierError::MissingLeadOut)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use thiserror::Error;
+
+use super::timestamp_format::{TimestampFormat, TimestampFormatError};
+
+/// Content of a POSS frame, which tells where in the audio a listener of a stream started.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PositionSynchronisation {
+    pub timestamp_format: TimestampFormat,
+    pub position: u64,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum PositionSynchronisationError {
+    #[error("Could not read enough bytes to parse the time stamp format")]
+    MissingTimestampFormat,
+    #[error("{0}")]
+    TimestampFormatError(TimestampFormatError),
+    #[error("The position is {0} bytes long, but at most 8 bytes are supported")]
+    PositionTooLong(usize),
+}
+
+impl PositionSynchronisation {
+    pub fn parse(bytes: &[u8]) -> Result<Self, PositionSynchronisationError> {
+        let Some((format_byte, position)) = bytes.split_first() else {
+            return Err(PositionSynchronisationError::MissingTimestampFormat);
+        };
+        let timestamp_format = TimestampFormat::try_from(*format_byte)
+            .map_err(PositionSynchronisationError::TimestampFormatError)?;
+        if position.len() > 8 {
+            return Err(PositionSynchronisationError::PositionTooLong(
+                position.len(),
+            ));
+        }
+
+        Ok(PositionSynchronisation {
+            timestamp_format,
+            position: position
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64),
+        })
+    }
+
+    /// The position is written with 4 bytes, or 8 if it doesn't fit.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.timestamp_format.binary_representation()];
+        match u32::try_from(self.position) {
+            Ok(position) => bytes.extend_from_slice(&position.to_be_bytes()),
+            Err(_) => bytes.extend_from_slice(&self.position.to_be_bytes()),
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn position_round_trip() {
+        let bytes = [2, 0, 0, 0xEA, 0x60];
+        let poss = PositionSynchronisation::parse(&bytes);
+        assert_eq!(
+            poss,
+            Ok(PositionSynchronisation {
+                timestamp_format: TimestampFormat::Milliseconds,
+                position: 60_000,
+            })
+        );
+        assert_eq!(poss.unwrap().to_bytes(), bytes);
+
+        // Positions that don't fit in 4 bytes take 8
+        let poss = PositionSynchronisation {
+            timestamp_format: TimestampFormat::MpegFrames,
+            position: 1 << 32,
+        };
+        let bytes = poss.to_bytes();
+        assert_eq!(bytes, [1, 0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(PositionSynchronisation::parse(&bytes), Ok(poss));
+    }
+
+    #[test]
+    fn invalid_positions() {
+        assert_eq!(
+            PositionSynchronisation::parse(&[]),
+            Err(PositionSynchronisationError::MissingTimestampFormat)
+        );
+        assert_eq!(
+            PositionSynchronisation::parse(&[2; 10]),
+            Err(PositionSynchronisationError::PositionTooLong(9))
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use thiserror::Error;
+
+/// Content of an RBUF frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RecommendedBufferSize {
+    /// in bytes, 24 bits at most
+    pub buffer_size: u32,
+    /// Tags of up to `buffer_size` bytes may occur in the audio stream
+    pub embedded_info: bool,
+    /// in bytes, from the end of this tag to the next one
+    pub offset_to_next_tag: Option<u32>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum RecommendedBufferSizeError {
+    #[error("The frame should be 4 or 8 bytes long, but it was {0}")]
+    InvalidSize(usize),
+    #[error("The buffer size {0} does not fit in 24 bits")]
+    BufferSizeTooLarge(u32),
+}
+
+impl RecommendedBufferSize {
+    pub fn parse(bytes: &[u8]) -> Result<Self, RecommendedBufferSizeError> {
+        let (b1, b2, b3, flags, offset_to_next_tag) = match *bytes {
+            [b1, b2, b3, flags] => (b1, b2, b3, flags, None),
+            [b1, b2, b3, flags, o1, o2, o3, o4] => (
+                b1,
+                b2,
+                b3,
+                flags,
+                Some(u32::from_be_bytes([o1, o2, o3, o4])),
+            ),
+            _ => return Err(RecommendedBufferSizeError::InvalidSize(bytes.len())),
+        };
+
+        Ok(RecommendedBufferSize {
+            buffer_size: u32::from_be_bytes([0, b1, b2, b3]),
+            embedded_info: flags & 1 != 0,
+            offset_to_next_tag,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RecommendedBufferSizeError> {
+        if self.buffer_size > 0xFF_FF_FF {
+            return Err(RecommendedBufferSizeError::BufferSizeTooLarge(
+                self.buffer_size,
+            ));
+        }
+        let mut bytes = self.buffer_size.to_be_bytes()[1..].to_vec();
+        bytes.push(self.embedded_info as u8);
+        if let Some(offset_to_next_tag) = self.offset_to_next_tag {
+            bytes.extend_from_slice(&offset_to_next_tag.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn buffer_size_only() {
+        let bytes = [0, 0x10, 0, 0];
+        let rbuf = RecommendedBufferSize::parse(&bytes);
+        assert_eq!(
+            rbuf,
+            Ok(RecommendedBufferSize {
+                buffer_size: 4096,
+                embedded_info: false,
+                offset_to_next_tag: None,
+            })
+        );
+        assert_eq!(rbuf.unwrap().to_bytes(), Ok(bytes.to_vec()));
+    }
+
+    #[test]
+    fn embedded_info_and_offset() {
+        let bytes = [0, 0x10, 0, 1, 0, 0, 0x20, 0];
+        let rbuf = RecommendedBufferSize::parse(&bytes);
+        assert_eq!(
+            rbuf,
+            Ok(RecommendedBufferSize {
+                buffer_size: 4096,
+                embedded_info: true,
+                offset_to_next_tag: Some(8192),
+            })
+        );
+        assert_eq!(rbuf.unwrap().to_bytes(), Ok(bytes.to_vec()));
+
+        assert_eq!(
+            RecommendedBufferSize::parse(&bytes[..6]),
+            Err(RecommendedBufferSizeError::InvalidSize(6))
+        );
+        let too_large = RecommendedBufferSize {
+            buffer_size: 0x01_00_00_00,
+            embedded_info: false,
+            offset_to_next_tag: None,
+        };
+        assert_eq!(
+            too_large.to_bytes(),
+            Err(RecommendedBufferSizeError::BufferSizeTooLarge(
+                0x01_00_00_00
+            ))
+        );
+    }
+}
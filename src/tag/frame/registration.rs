@@ -0,0 +1,127 @@
+use thiserror::Error;
+
+use crate::{tag::encoding::Encoding, utils::string_to_latin1};
+
+/// Content of an ENCR frame, which registers the method symbol used by encrypted frames.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EncryptionMethodRegistration {
+    /// URL or email address of the organisation responsible for the encryption method
+    pub owner_identifier: String,
+    pub method_symbol: u8,
+    pub encryption_data: Vec<u8>,
+}
+
+/// Content of a GRID frame, which registers the group symbol used by grouped frames.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GroupIdentificationRegistration {
+    /// URL or email address of the organisation responsible for the grouping
+    pub owner_identifier: String,
+    pub group_symbol: u8,
+    pub group_dependent_data: Vec<u8>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum RegistrationError {
+    #[error("Could not read enough bytes to parse the owner identifier and symbol")]
+    MissingData,
+    #[error("The symbol {0:#04X} is reserved. Only values from 0x80 to 0xF0 may be registered")]
+    ReservedSymbol(u8),
+}
+
+fn parse_registration(bytes: &[u8]) -> Result<(String, u8, Vec<u8>), RegistrationError> {
+    if !bytes.contains(&0) {
+        return Err(RegistrationError::MissingData);
+    }
+    let (owner_identifier, bytes) = Encoding::Latin1.read_string(bytes);
+    let Some((symbol, data)) = bytes.split_first() else {
+        return Err(RegistrationError::MissingData);
+    };
+    Ok((owner_identifier, *symbol, data.to_vec()))
+}
+
+fn registration_to_bytes(
+    owner_identifier: &str,
+    symbol: u8,
+    data: &[u8],
+) -> Result<Vec<u8>, RegistrationError> {
+    if !(0x80..=0xF0).contains(&symbol) {
+        return Err(RegistrationError::ReservedSymbol(symbol));
+    }
+    let mut bytes = string_to_latin1(owner_identifier);
+    bytes.push(0);
+    bytes.push(symbol);
+    bytes.extend_from_slice(data);
+    Ok(bytes)
+}
+
+impl EncryptionMethodRegistration {
+    pub fn parse(bytes: &[u8]) -> Result<Self, RegistrationError> {
+        let (owner_identifier, method_symbol, encryption_data) = parse_registration(bytes)?;
+        Ok(EncryptionMethodRegistration {
+            owner_identifier,
+            method_symbol,
+            encryption_data,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RegistrationError> {
+        registration_to_bytes(
+            &self.owner_identifier,
+            self.method_symbol,
+            &self.encryption_data,
+        )
+    }
+}
+
+impl GroupIdentificationRegistration {
+    pub fn parse(bytes: &[u8]) -> Result<Self, RegistrationError> {
+        let (owner_identifier, group_symbol, group_dependent_data) = parse_registration(bytes)?;
+        Ok(GroupIdentificationRegistration {
+            owner_identifier,
+            group_symbol,
+            group_dependent_data,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RegistrationError> {
+        registration_to_bytes(
+            &self.owner_identifier,
+            self.group_symbol,
+            &self.group_dependent_data,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_registration() {
+        let bytes = b"mailto:drm@example.com\x00\x81key-id".to_vec();
+        let encr = EncryptionMethodRegistration::parse(&bytes);
+        assert_eq!(
+            encr,
+            Ok(EncryptionMethodRegistration {
+                owner_identifier: String::from("mailto:drm@example.com"),
+                method_symbol: 0x81,
+                encryption_data: b"key-id".to_vec(),
+            })
+        );
+        assert_eq!(encr.unwrap().to_bytes(), Ok(bytes));
+    }
+
+    #[test]
+    fn reserved_symbol() {
+        let grid = GroupIdentificationRegistration {
+            owner_identifier: String::from("http://example.com"),
+            group_symbol: 0x10,
+            group_dependent_data: vec![],
+        };
+        assert_eq!(
+            grid.to_bytes(),
+            Err(RegistrationError::ReservedSymbol(0x10))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use thiserror::Error;
+
+/// Content of an RVRB frame.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Reverb {
+    /// in milliseconds
+    pub reverb_left: u16,
+    /// in milliseconds
+    pub reverb_right: u16,
+    pub bounces_left: u8,
+    pub bounces_right: u8,
+    pub feedback_left_to_left: u8,
+    pub feedback_left_to_right: u8,
+    pub feedback_right_to_right: u8,
+    pub feedback_right_to_left: u8,
+    pub premix_left_to_right: u8,
+    pub premix_right_to_left: u8,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum ReverbError {
+    #[error("The reverb frame should be 12 bytes long, but it was {0}")]
+    InvalidSize(usize),
+}
+
+impl Reverb {
+    pub fn parse(bytes: &[u8]) -> Result<Self, ReverbError> {
+        let [l1, l2, r1, r2, bounces_left, bounces_right, feedback_left_to_left, feedback_left_to_right, feedback_right_to_right, feedback_right_to_left, premix_left_to_right, premix_right_to_left] =
+            *bytes
+        else {
+            return Err(ReverbError::InvalidSize(bytes.len()));
+        };
+
+        Ok(Reverb {
+            reverb_left: u16::from_be_bytes([l1, l2]),
+            reverb_right: u16::from_be_bytes([r1, r2]),
+            bounces_left,
+            bounces_right,
+            feedback_left_to_left,
+            feedback_left_to_right,
+            feedback_right_to_right,
+            feedback_right_to_left,
+            premix_left_to_right,
+            premix_right_to_left,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12);
+        bytes.extend_from_slice(&self.reverb_left.to_be_bytes());
+        bytes.extend_from_slice(&self.reverb_right.to_be_bytes());
+        bytes.extend_from_slice(&[
+            self.bounces_left,
+            self.bounces_right,
+            self.feedback_left_to_left,
+            self.feedback_left_to_right,
+            self.feedback_right_to_right,
+            self.feedback_right_to_left,
+            self.premix_left_to_right,
+            self.premix_right_to_left,
+        ]);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_reverb() {
+        let bytes = [
+            0x01, 0x2C, 0x00, 0xFA, 3, 4, 0x80, 0x10, 0x80, 0x10, 0x20, 0x20,
+        ];
+        let rvrb = Reverb::parse(&bytes);
+        assert_eq!(
+            rvrb,
+            Ok(Reverb {
+                reverb_left: 300,
+                reverb_right: 250,
+                bounces_left: 3,
+                bounces_right: 4,
+                feedback_left_to_left: 0x80,
+                feedback_left_to_right: 0x10,
+                feedback_right_to_right: 0x80,
+                feedback_right_to_left: 0x10,
+                premix_left_to_right: 0x20,
+                premix_right_to_left: 0x20,
+            })
+        );
+        assert_eq!(rvrb.unwrap().to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,49 @@
+use thiserror::Error;
+
+/// Content of a SEEK frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Seek {
+    /// in bytes, from the end of this tag to the beginning of the next one
+    pub minimum_offset_to_next_tag: u32,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum SeekError {
+    #[error("The frame should be 4 bytes long, but it was {0}")]
+    InvalidSize(usize),
+}
+
+impl Seek {
+    pub fn parse(bytes: &[u8]) -> Result<Self, SeekError> {
+        let [a, b, c, d] = *bytes else {
+            return Err(SeekError::InvalidSize(bytes.len()));
+        };
+        Ok(Seek {
+            minimum_offset_to_next_tag: u32::from_be_bytes([a, b, c, d]),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.minimum_offset_to_next_tag.to_be_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn seek_round_trip() {
+        let bytes = [0, 1, 0x86, 0xA0];
+        let seek = Seek::parse(&bytes);
+        assert_eq!(
+            seek,
+            Ok(Seek {
+                minimum_offset_to_next_tag: 100_000
+            })
+        );
+        assert_eq!(seek.unwrap().to_bytes(), bytes);
+        assert_eq!(Seek::parse(&[0, 1, 0]), Err(SeekError::InvalidSize(3)));
+    }
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+/// Content of a SIGN frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Signature {
+    pub group_symbol: u8,
+    pub signature: Vec<u8>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum SignatureFrameError {
+    #[error("Could not read enough bytes to parse the group symbol")]
+    MissingGroupSymbol,
+}
+
+impl Signature {
+    pub fn parse(bytes: &[u8]) -> Result<Self, SignatureFrameError> {
+        let Some((group_symbol, signature)) = bytes.split_first() else {
+            return Err(SignatureFrameError::MissingGroupSymbol);
+        };
+        Ok(Signature {
+            group_symbol: *group_symbol,
+            signature: signature.to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.group_symbol];
+        bytes.extend_from_slice(&self.signature);
+        bytes
+    }
+}
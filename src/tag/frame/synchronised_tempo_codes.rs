@@ -0,0 +1,166 @@
+use thiserror::Error;
+
+use super::timestamp_format::{TimestampFormat, TimestampFormatError};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Tempo {
+    /// A beat-free time period, which is not the same as a music-free time period
+    BeatFree,
+    /// One single beat-stroke followed by a beat-free period
+    SingleBeat,
+    /// Beats per minute, in the range 2 - 510
+    Bpm(u16),
+}
+
+impl Tempo {
+    pub const MAX_BPM: u16 = 510;
+
+    fn from_value(value: u16) -> Self {
+        match value {
+            0 => Tempo::BeatFree,
+            1 => Tempo::SingleBeat,
+            bpm => Tempo::Bpm(bpm),
+        }
+    }
+
+    fn value(&self) -> u16 {
+        match self {
+            Tempo::BeatFree => 0,
+            Tempo::SingleBeat => 1,
+            Tempo::Bpm(bpm) => *bpm,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TempoCode {
+    pub tempo: Tempo,
+    pub timestamp: u32,
+}
+
+/// Content of a SYTC frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SynchronisedTempoCodes {
+    pub timestamp_format: TimestampFormat,
+    pub tempo_codes: Vec<TempoCode>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum SynchronisedTempoCodesError {
+    #[error("Could not read enough bytes to parse the time stamp format")]
+    MissingTimestampFormat,
+    #[error("{0}")]
+    TimestampFormatError(TimestampFormatError),
+    #[error("The tempo code {0} ended before its time stamp could be read")]
+    TruncatedTempoCode(usize),
+    #[error("The tempo {0} BPM is out of range. It should be between 2 and 510")]
+    InvalidBpm(u16),
+}
+
+impl SynchronisedTempoCodes {
+    pub fn parse(bytes: &[u8]) -> Result<Self, SynchronisedTempoCodesError> {
+        let Some((format_byte, mut bytes)) = bytes.split_first() else {
+            return Err(SynchronisedTempoCodesError::MissingTimestampFormat);
+        };
+        let timestamp_format = TimestampFormat::try_from(*format_byte)
+            .map_err(SynchronisedTempoCodesError::TimestampFormatError)?;
+
+        let mut tempo_codes = vec![];
+        while !bytes.is_empty() {
+            let truncated = SynchronisedTempoCodesError::TruncatedTempoCode(tempo_codes.len() + 1);
+            // A first byte of $FF means one more byte follows, which is added to the first.
+            let (tempo, rest) = match bytes {
+                [0xFF, extra, rest @ ..] => (0xFF + *extra as u16, rest),
+                [0xFF] => return Err(truncated),
+                [first, rest @ ..] => (*first as u16, rest),
+                [] => unreachable!(),
+            };
+            let [a, b, c, d, rest @ ..] = rest else {
+                return Err(truncated);
+            };
+            tempo_codes.push(TempoCode {
+                tempo: Tempo::from_value(tempo),
+                timestamp: u32::from_be_bytes([*a, *b, *c, *d]),
+            });
+            bytes = rest;
+        }
+
+        Ok(SynchronisedTempoCodes {
+            timestamp_format,
+            tempo_codes,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SynchronisedTempoCodesError> {
+        let mut bytes = vec![self.timestamp_format.binary_representation()];
+        for tempo_code in &self.tempo_codes {
+            let value = match tempo_code.tempo {
+                Tempo::Bpm(bpm) if !(2..=Tempo::MAX_BPM).contains(&bpm) => {
+                    return Err(SynchronisedTempoCodesError::InvalidBpm(bpm))
+                }
+                tempo => tempo.value(),
+            };
+            if value >= 0xFF {
+                bytes.extend_from_slice(&[0xFF, (value - 0xFF) as u8]);
+            } else {
+                bytes.push(value as u8);
+            }
+            bytes.extend_from_slice(&tempo_code.timestamp.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extension_byte() {
+        let bytes = [
+            1, 0, 0, 0, 0, 0, 0x78, 0, 0, 0, 10, 0xFF, 0x2D, 0, 0, 0, 20, 0xFF, 0, 0, 0, 0, 30,
+        ];
+        let sytc = SynchronisedTempoCodes::parse(&bytes);
+        assert_eq!(
+            sytc,
+            Ok(SynchronisedTempoCodes {
+                timestamp_format: TimestampFormat::MpegFrames,
+                tempo_codes: vec![
+                    TempoCode {
+                        tempo: Tempo::BeatFree,
+                        timestamp: 0
+                    },
+                    TempoCode {
+                        tempo: Tempo::Bpm(120),
+                        timestamp: 10
+                    },
+                    TempoCode {
+                        tempo: Tempo::Bpm(300),
+                        timestamp: 20
+                    },
+                    TempoCode {
+                        tempo: Tempo::Bpm(255),
+                        timestamp: 30
+                    },
+                ]
+            })
+        );
+        assert_eq!(sytc.unwrap().to_bytes(), Ok(bytes.to_vec()));
+    }
+
+    #[test]
+    fn out_of_range_bpm() {
+        let sytc = SynchronisedTempoCodes {
+            timestamp_format: TimestampFormat::Milliseconds,
+            tempo_codes: vec![TempoCode {
+                tempo: Tempo::Bpm(511),
+                timestamp: 0,
+            }],
+        };
+        assert_eq!(
+            sytc.to_bytes(),
+            Err(SynchronisedTempoCodesError::InvalidBpm(511))
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use thiserror::Error;
+
+use crate::tag::encoding::{Encoding, EncodingError};
+
+/// Content of a USER frame.
+#[derive(PartialEq, Clone, Debug)]
+pub struct TermsOfUse {
+    pub encoding: Encoding,
+    /// ISO-639-2 language code
+    pub language: [u8; 3],
+    pub text: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum TermsOfUseError {
+    #[error("Could not read enough bytes to parse the encoding and language")]
+    MissingData,
+    #[error("Encoding Error: {0}")]
+    EncodingError(EncodingError),
+}
+
+impl TermsOfUse {
+    pub fn parse(bytes: &[u8]) -> Result<Self, TermsOfUseError> {
+        let [encoding_byte, l1, l2, l3, text @ ..] = bytes else {
+            return Err(TermsOfUseError::MissingData);
+        };
+        let encoding =
+            Encoding::from_encoding_byte(*encoding_byte).map_err(TermsOfUseError::EncodingError)?;
+        let (text, _) = encoding.read_string(text);
+
+        Ok(TermsOfUse {
+            encoding,
+            language: [*l1, *l2, *l3],
+            text,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.encoding.encoding_byte()];
+        bytes.extend_from_slice(&self.language);
+        bytes.extend(self.encoding.encode_string(&self.text));
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn simple_terms_of_use() {
+        let bytes = b"\x03engAll rights reserved \xC2\xA9".to_vec();
+        let user = TermsOfUse::parse(&bytes);
+        assert_eq!(
+            user,
+            Ok(TermsOfUse {
+                encoding: Encoding::UTF8,
+                language: *b"eng",
+                text: String::from("All rights reserved ©"),
+            })
+        );
+        assert_eq!(user.unwrap().to_bytes(), bytes);
+    }
+}
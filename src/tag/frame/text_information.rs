@@ -0,0 +1,235 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::tag::encoding::{Encoding, EncodingError};
+
+use super::text_values::{parse_languages, FileType, Isrc, MediaType, MusicalKey, TextValueError};
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum TextInformationError {
+    #[error("Could not read enough bytes to parse the data")]
+    MissingData,
+    #[error("Encoding Error: {0}")]
+    EncodingError(EncodingError),
+}
+
+/// Content of the text information frames, whose id starts with a T. ID3v2.4 frames may hold
+/// several strings.
+#[derive(PartialEq, Clone, Debug)]
+pub struct TextInformation {
+    encoding: Encoding,
+    strings: Vec<String>,
+}
+
+impl TextInformation {
+    pub fn new(encoding: Encoding, strings: Vec<String>) -> Self {
+        TextInformation { encoding, strings }
+    }
+
+    /// Numbers, codes and other values that are only made of ASCII characters are written in
+    /// Latin-1.
+    fn from_value(value: impl ToString) -> Self {
+        TextInformation::new(Encoding::Latin1, vec![value.to_string()])
+    }
+
+    pub fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    pub fn strings(&self) -> &[String] {
+        &self.strings
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, TextInformationError> {
+        let Some((encoding_byte, mut bytes)) = bytes.split_first() else {
+            return Err(TextInformationError::MissingData);
+        };
+        let encoding = Encoding::from_encoding_byte(*encoding_byte)
+            .map_err(TextInformationError::EncodingError)?;
+
+        let mut strings = vec![];
+        while !bytes.is_empty() {
+            let (string, rest) = encoding.read_string(bytes);
+            strings.push(string);
+            bytes = rest;
+        }
+
+        Ok(TextInformation { encoding, strings })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.encoding.encoding_byte()];
+        for (index, string) in self.strings.iter().enumerate() {
+            if index > 0 {
+                bytes.extend_from_slice(self.encoding.string_separator());
+            }
+            bytes.extend(self.encoding.encode_string(string));
+        }
+        bytes
+    }
+
+    /// The first string, without surrounding whitespace.
+    fn value(&self) -> Result<&str, TextValueError> {
+        self.strings
+            .first()
+            .map(|string| string.trim())
+            .filter(|string| !string.is_empty())
+            .ok_or(TextValueError::MissingValue)
+    }
+
+    fn number(&self) -> Result<u64, TextValueError> {
+        let value = self.value()?;
+        value
+            .parse()
+            .map_err(|_| TextValueError::InvalidNumber(value.to_string()))
+    }
+
+    /// Beats per minute of a TBPM frame.
+    pub fn bpm(&self) -> Result<u32, TextValueError> {
+        let bpm = self.number()?;
+        u32::try_from(bpm).map_err(|_| TextValueError::InvalidNumber(bpm.to_string()))
+    }
+
+    pub fn from_bpm(bpm: u32) -> Self {
+        TextInformation::from_value(bpm)
+    }
+
+    /// Length of the audio of a TLEN frame, stored in milliseconds.
+    pub fn length(&self) -> Result<Duration, TextValueError> {
+        self.number().map(Duration::from_millis)
+    }
+
+    pub fn from_length(length: Duration) -> Self {
+        TextInformation::from_value(length.as_millis())
+    }
+
+    /// Silence between every song of a playlist, from a TDLY frame, stored in milliseconds.
+    pub fn playlist_delay(&self) -> Result<Duration, TextValueError> {
+        self.number().map(Duration::from_millis)
+    }
+
+    pub fn from_playlist_delay(delay: Duration) -> Self {
+        TextInformation::from_value(delay.as_millis())
+    }
+
+    /// Initial key of a TKEY frame.
+    pub fn musical_key(&self) -> Result<MusicalKey, TextValueError> {
+        self.value()?.parse()
+    }
+
+    pub fn from_musical_key(key: MusicalKey) -> Self {
+        TextInformation::from_value(key)
+    }
+
+    /// ISO-639-2 codes of the languages of a TLAN frame.
+    pub fn languages(&self) -> Result<Vec<[u8; 3]>, TextValueError> {
+        let languages = self
+            .strings
+            .iter()
+            .filter(|string| !string.trim().is_empty())
+            .map(|string| parse_languages(string))
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        if languages.is_empty() {
+            return Err(TextValueError::MissingValue);
+        }
+        Ok(languages)
+    }
+
+    /// Fails if a code isn't made of 3 letters.
+    pub fn from_languages(languages: &[[u8; 3]]) -> Result<Self, TextValueError> {
+        let strings = languages
+            .iter()
+            .map(|code| {
+                let code = String::from_utf8_lossy(code);
+                parse_languages(&code).map(|_| code.to_ascii_lowercase())
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(TextInformation::new(Encoding::Latin1, strings))
+    }
+
+    /// International Standard Recording Code of a TSRC frame.
+    pub fn isrc(&self) -> Result<Isrc, TextValueError> {
+        self.value()?.parse()
+    }
+
+    pub fn from_isrc(isrc: &Isrc) -> Self {
+        TextInformation::from_value(isrc)
+    }
+
+    /// File type of a TFLT frame.
+    pub fn file_type(&self) -> Result<FileType, TextValueError> {
+        self.value()?.parse()
+    }
+
+    pub fn from_file_type(file_type: &FileType) -> Self {
+        TextInformation::from_value(file_type)
+    }
+
+    /// Media types of a TMED frame.
+    pub fn media_types(&self) -> Result<Vec<MediaType>, TextValueError> {
+        let media_types = self
+            .strings
+            .iter()
+            .filter(|string| !string.trim().is_empty())
+            .map(|string| string.parse())
+            .collect::<Result<Vec<MediaType>, _>>()?;
+        if media_types.is_empty() {
+            return Err(TextValueError::MissingValue);
+        }
+        Ok(media_types)
+    }
+
+    pub fn from_media_types(media_types: &[MediaType]) -> Self {
+        TextInformation::new(
+            Encoding::Latin1,
+            media_types.iter().map(MediaType::to_string).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn several_strings() {
+        let bytes = b"\x03Rock\x00Pop".to_vec();
+        let text = TextInformation::parse(&bytes).unwrap();
+        assert_eq!(text.strings(), &[String::from("Rock"), String::from("Pop")]);
+        assert_eq!(text.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn numeric_values() {
+        let tbpm = TextInformation::parse(b"\x00128\x00").unwrap();
+        assert_eq!(tbpm.bpm(), Ok(128));
+        assert_eq!(TextInformation::from_bpm(128).to_bytes(), b"\x00128");
+
+        let tlen = TextInformation::from_length(Duration::from_secs(215));
+        assert_eq!(tlen.to_bytes(), b"\x00215000");
+        assert_eq!(tlen.length(), Ok(Duration::from_secs(215)));
+
+        let free_form = TextInformation::parse(b"\x00about 3 minutes").unwrap();
+        assert_eq!(
+            free_form.length(),
+            Err(TextValueError::InvalidNumber(String::from(
+                "about 3 minutes"
+            )))
+        );
+        assert_eq!(
+            TextInformation::parse(b"\x00").unwrap().bpm(),
+            Err(TextValueError::MissingValue)
+        );
+    }
+
+    #[test]
+    fn languages() {
+        let tlan = TextInformation::from_languages(&[*b"eng", *b"FRE"]).unwrap();
+        assert_eq!(tlan.to_bytes(), b"\x00eng\x00fre");
+        assert_eq!(tlan.languages(), Ok(vec![*b"eng", *b"fre"]));
+        assert!(TextInformation::from_languages(&[*b"e1g"]).is_err());
+    }
+}
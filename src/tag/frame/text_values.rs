@@ -0,0 +1,475 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum TextValueError {
+    #[error("The frame holds no value")]
+    MissingValue,
+    #[error("{0:?} is not a positive integer")]
+    InvalidNumber(String),
+    #[error("{0:?} is not a musical key. It should be like \"A\", \"Bbm\", \"F#\" or \"o\"")]
+    InvalidMusicalKey(String),
+    #[error("{0:?} is not an ISO-639-2 language code")]
+    InvalidLanguage(String),
+    #[error("{0:?} is not an ISRC. It should be like \"USRC17607839\"")]
+    InvalidIsrc(String),
+    #[error("{1:?} is not a refinement of {0:?}")]
+    InvalidRefinement(String, String),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Accidental {
+    Flat,
+    Sharp,
+}
+
+/// Value of a TKEY frame.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MusicalKey {
+    Key {
+        /// From 'A' to 'G'
+        note: char,
+        accidental: Option<Accidental>,
+        minor: bool,
+    },
+    OffKey,
+}
+
+impl FromStr for MusicalKey {
+    type Err = TextValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TextValueError::InvalidMusicalKey(s.to_string());
+        if s == "o" {
+            return Ok(MusicalKey::OffKey);
+        }
+
+        let mut chars = s.chars().peekable();
+        let note = chars
+            .next()
+            .filter(|note| ('A'..='G').contains(note))
+            .ok_or_else(invalid)?;
+        let accidental = match chars.peek() {
+            Some('b') => Some(Accidental::Flat),
+            Some('#') => Some(Accidental::Sharp),
+            _ => None,
+        };
+        if accidental.is_some() {
+            chars.next();
+        }
+        let minor = chars.next_if_eq(&'m').is_some();
+        if chars.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(MusicalKey::Key {
+            note,
+            accidental,
+            minor,
+        })
+    }
+}
+
+impl Display for MusicalKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MusicalKey::OffKey => write!(f, "o"),
+            MusicalKey::Key {
+                note,
+                accidental,
+                minor,
+            } => {
+                write!(f, "{note}")?;
+                match accidental {
+                    Some(Accidental::Flat) => write!(f, "b")?,
+                    Some(Accidental::Sharp) => write!(f, "#")?,
+                    None => {}
+                }
+                if *minor {
+                    write!(f, "m")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses the ISO-639-2 codes of a TLAN string. ID3v2.3 tags may hold several codes in a single
+/// string.
+pub fn parse_languages(s: &str) -> Result<Vec<[u8; 3]>, TextValueError> {
+    let bytes = s.trim().as_bytes();
+    if bytes.is_empty() || !bytes.len().is_multiple_of(3) {
+        return Err(TextValueError::InvalidLanguage(s.to_string()));
+    }
+    bytes
+        .chunks_exact(3)
+        .map(|code| match code {
+            [a, b, c] if code.iter().all(u8::is_ascii_alphabetic) => Ok([
+                a.to_ascii_lowercase(),
+                b.to_ascii_lowercase(),
+                c.to_ascii_lowercase(),
+            ]),
+            _ => Err(TextValueError::InvalidLanguage(s.to_string())),
+        })
+        .collect()
+}
+
+/// Value of a TSRC frame: an International Standard Recording Code.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Isrc(String);
+
+impl Isrc {
+    /// ISO 3166-1 alpha-2 code of the country of the registrant
+    pub fn country_code(&self) -> &str {
+        &self.0[..2]
+    }
+
+    pub fn registrant_code(&self) -> &str {
+        &self.0[2..5]
+    }
+
+    /// Last 2 digits of the year of reference
+    pub fn year(&self) -> &str {
+        &self.0[5..7]
+    }
+
+    pub fn designation_code(&self) -> &str {
+        &self.0[7..]
+    }
+}
+
+impl FromStr for Isrc {
+    type Err = TextValueError;
+
+    /// Accepts the hyphenated form, as in "US-RC1-76-07839", and lowercase letters.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s
+            .trim()
+            .chars()
+            .filter(|c| *c != '-')
+            .map(|c| c.to_ascii_uppercase())
+            .collect::<String>();
+        let is_valid = code.len() == 12
+            && code.char_indices().all(|(index, c)| match index {
+                0..=1 => c.is_ascii_uppercase(),
+                2..=4 => c.is_ascii_uppercase() || c.is_ascii_digit(),
+                _ => c.is_ascii_digit(),
+            });
+        if !is_valid {
+            return Err(TextValueError::InvalidIsrc(s.to_string()));
+        }
+        Ok(Isrc(code))
+    }
+}
+
+impl Display for Isrc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Refinements of the MPEG audio file type.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MpegAudioType {
+    Layer1,
+    Layer2,
+    Layer3,
+    Mpeg25,
+    AdvancedAudioCompression,
+}
+
+impl MpegAudioType {
+    fn code(&self) -> &'static str {
+        match self {
+            MpegAudioType::Layer1 => "1",
+            MpegAudioType::Layer2 => "2",
+            MpegAudioType::Layer3 => "3",
+            MpegAudioType::Mpeg25 => "2.5",
+            MpegAudioType::AdvancedAudioCompression => "AAC",
+        }
+    }
+}
+
+/// Value of a TFLT frame. When the frame is missing, MPEG audio is assumed.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum FileType {
+    /// The MIME type is given in the following strings
+    Mime,
+    MpegAudio(Option<MpegAudioType>),
+    /// Transform-domain Weighted Interleave Vector Quantisation
+    Vqf,
+    /// Pulse Code Modulated audio
+    Pcm,
+    Other(String),
+}
+
+impl FromStr for FileType {
+    type Err = TextValueError;
+
+    /// Codes that aren't predefined are kept as [`FileType::Other`], but a predefined code with
+    /// an unknown refinement is an error.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (code, refinement) = match s.split_once('/') {
+            Some((code, refinement)) => (code, Some(refinement)),
+            None => (s, None),
+        };
+        let invalid_refinement = |refinement: &str| {
+            TextValueError::InvalidRefinement(code.to_string(), refinement.to_string())
+        };
+
+        let file_type = match code {
+            "MIME" => FileType::Mime,
+            "MPG" => {
+                let mpeg_audio_type = match refinement {
+                    None => None,
+                    Some(refinement) => Some(
+                        [
+                            MpegAudioType::Layer1,
+                            MpegAudioType::Layer2,
+                            MpegAudioType::Layer3,
+                            MpegAudioType::Mpeg25,
+                            MpegAudioType::AdvancedAudioCompression,
+                        ]
+                        .into_iter()
+                        .find(|typ| typ.code() == refinement)
+                        .ok_or_else(|| invalid_refinement(refinement))?,
+                    ),
+                };
+                return Ok(FileType::MpegAudio(mpeg_audio_type));
+            }
+            "VQF" => FileType::Vqf,
+            "PCM" => FileType::Pcm,
+            _ => return Ok(FileType::Other(s.to_string())),
+        };
+        match refinement {
+            Some(refinement) => Err(invalid_refinement(refinement)),
+            None => Ok(file_type),
+        }
+    }
+}
+
+impl Display for FileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileType::Mime => write!(f, "MIME"),
+            FileType::MpegAudio(None) => write!(f, "MPG"),
+            FileType::MpegAudio(Some(typ)) => write!(f, "MPG/{}", typ.code()),
+            FileType::Vqf => write!(f, "VQF"),
+            FileType::Pcm => write!(f, "PCM"),
+            FileType::Other(other) => write!(f, "{other}"),
+        }
+    }
+}
+
+/// Predefined media of the TMED frame.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Medium {
+    OtherDigital,
+    OtherAnalogue,
+    CompactDisc,
+    Laserdisc,
+    Turntable,
+    MiniDisc,
+    Dat,
+    Dcc,
+    Dvd,
+    Television,
+    Video,
+    Radio,
+    Telephone,
+    MusicCassette,
+    ReelToReel,
+}
+
+const MEDIA: [Medium; 15] = [
+    Medium::OtherDigital,
+    Medium::OtherAnalogue,
+    Medium::CompactDisc,
+    Medium::Laserdisc,
+    Medium::Turntable,
+    Medium::MiniDisc,
+    Medium::Dat,
+    Medium::Dcc,
+    Medium::Dvd,
+    Medium::Television,
+    Medium::Video,
+    Medium::Radio,
+    Medium::Telephone,
+    Medium::MusicCassette,
+    Medium::ReelToReel,
+];
+
+impl Medium {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Medium::OtherDigital => "DIG",
+            Medium::OtherAnalogue => "ANA",
+            Medium::CompactDisc => "CD",
+            Medium::Laserdisc => "LD",
+            Medium::Turntable => "TT",
+            Medium::MiniDisc => "MD",
+            Medium::Dat => "DAT",
+            Medium::Dcc => "DCC",
+            Medium::Dvd => "DVD",
+            Medium::Television => "TV",
+            Medium::Video => "VID",
+            Medium::Radio => "RAD",
+            Medium::Telephone => "TEL",
+            Medium::MusicCassette => "MC",
+            Medium::ReelToReel => "REE",
+        }
+    }
+
+    /// Refinements the spec defines for the medium, as in "TT/45" for a 45 rpm record.
+    pub fn refinements(&self) -> &'static [&'static str] {
+        match self {
+            Medium::OtherDigital => &["A"],
+            Medium::OtherAnalogue => &["WAC", "8CA"],
+            Medium::CompactDisc => &["A", "DD", "AD", "AA"],
+            Medium::Laserdisc => &["A"],
+            Medium::Turntable => &["33", "45", "71", "76", "78", "80"],
+            Medium::MiniDisc => &["A"],
+            Medium::Dat => &["A", "1", "2", "3", "4", "5", "6"],
+            Medium::Dcc => &["A"],
+            Medium::Dvd => &["A"],
+            Medium::Television => &["PAL", "NTSC", "SECAM"],
+            Medium::Video => &["PAL", "NTSC", "SECAM", "VHS", "SVHS", "BETA"],
+            Medium::Radio => &["FM", "AM", "LW", "MW"],
+            Medium::Telephone => &["I"],
+            Medium::MusicCassette => &["4", "9", "I", "II", "III", "IV"],
+            Medium::ReelToReel => &["9", "19", "38", "76", "I", "II", "III", "IV"],
+        }
+    }
+}
+
+/// Value of a TMED frame.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum MediaType {
+    Predefined {
+        medium: Medium,
+        /// Applied in order, as in "VID/PAL/VHS"
+        refinements: Vec<String>,
+    },
+    Other(String),
+}
+
+impl MediaType {
+    pub fn new(medium: Medium, refinements: &[&str]) -> Result<Self, TextValueError> {
+        if let Some(refinement) = refinements
+            .iter()
+            .find(|refinement| !medium.refinements().contains(refinement))
+        {
+            return Err(TextValueError::InvalidRefinement(
+                medium.code().to_string(),
+                refinement.to_string(),
+            ));
+        }
+        Ok(MediaType::Predefined {
+            medium,
+            refinements: refinements.iter().map(|r| r.to_string()).collect(),
+        })
+    }
+}
+
+impl FromStr for MediaType {
+    type Err = TextValueError;
+
+    /// Also accepts the ID3v2.3 form, where the predefined types are enclosed in parentheses.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let value = s
+            .strip_prefix('(')
+            .and_then(|value| value.strip_suffix(')'))
+            .unwrap_or(s);
+        let mut parts = value.split('/');
+        let code = parts.next().unwrap_or_default();
+
+        match MEDIA.into_iter().find(|medium| medium.code() == code) {
+            Some(medium) => MediaType::new(medium, &parts.collect::<Vec<&str>>()),
+            None => Ok(MediaType::Other(s.to_string())),
+        }
+    }
+}
+
+impl Display for MediaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MediaType::Predefined {
+                medium,
+                refinements,
+            } => {
+                write!(f, "{}", medium.code())?;
+                for refinement in refinements {
+                    write!(f, "/{refinement}")?;
+                }
+                Ok(())
+            }
+            MediaType::Other(other) => write!(f, "{other}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn musical_keys() {
+        assert_eq!(
+            "Bbm".parse(),
+            Ok(MusicalKey::Key {
+                note: 'B',
+                accidental: Some(Accidental::Flat),
+                minor: true,
+            })
+        );
+        assert_eq!("o".parse::<MusicalKey>().unwrap().to_string(), "o");
+        assert_eq!("F#".parse::<MusicalKey>().unwrap().to_string(), "F#");
+        for invalid in ["", "H", "C major", "bB", "A#m7"] {
+            assert_eq!(
+                invalid.parse::<MusicalKey>(),
+                Err(TextValueError::InvalidMusicalKey(invalid.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn isrc() {
+        let isrc = "us-rc1-76-07839".parse::<Isrc>().unwrap();
+        assert_eq!(isrc.to_string(), "USRC17607839");
+        assert_eq!(isrc.country_code(), "US");
+        assert_eq!(isrc.registrant_code(), "RC1");
+        assert_eq!(isrc.year(), "76");
+        assert_eq!(isrc.designation_code(), "07839");
+        assert!("USRC1760783".parse::<Isrc>().is_err());
+        assert!("1SRC17607839".parse::<Isrc>().is_err());
+    }
+
+    #[test]
+    fn file_and_media_types() {
+        assert_eq!(
+            "MPG/2.5".parse(),
+            Ok(FileType::MpegAudio(Some(MpegAudioType::Mpeg25)))
+        );
+        assert_eq!(
+            "PCM/3".parse::<FileType>(),
+            Err(TextValueError::InvalidRefinement(
+                String::from("PCM"),
+                String::from("3")
+            ))
+        );
+        assert_eq!("FLAC".parse(), Ok(FileType::Other(String::from("FLAC"))));
+
+        let media_type = "(VID/PAL/VHS)".parse::<MediaType>().unwrap();
+        assert_eq!(
+            media_type,
+            MediaType::new(Medium::Video, &["PAL", "VHS"]).unwrap()
+        );
+        assert_eq!(media_type.to_string(), "VID/PAL/VHS");
+        assert!("TT/33/9".parse::<MediaType>().is_err());
+        assert_eq!(parse_languages("engFRE"), Ok(vec![*b"eng", *b"fre"]));
+    }
+}
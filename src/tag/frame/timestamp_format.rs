@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+/// Unit used by the time stamps of ETCO, SYTC, SYLT and POSS frames.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TimestampFormat {
+    /// Absolute time, 32 bit sized, using MPEG frames as unit
+    MpegFrames,
+    /// Absolute time, 32 bit sized, using milliseconds as unit
+    Milliseconds,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum TimestampFormatError {
+    #[error("The time stamp format byte {0} is unknown. It should either be 01 or 02")]
+    UnknownTimestampFormat(u8),
+}
+
+impl TryFrom<u8> for TimestampFormat {
+    type Error = TimestampFormatError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(TimestampFormat::MpegFrames),
+            2 => Ok(TimestampFormat::Milliseconds),
+            _ => Err(TimestampFormatError::UnknownTimestampFormat(value)),
+        }
+    }
+}
+
+impl TimestampFormat {
+    pub fn binary_representation(&self) -> u8 {
+        match self {
+            TimestampFormat::MpegFrames => 1,
+            TimestampFormat::Milliseconds => 2,
+        }
+    }
+}
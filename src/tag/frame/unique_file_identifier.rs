@@ -0,0 +1,138 @@
+use thiserror::Error;
+
+use crate::{
+    tag::encoding::Encoding,
+    utils::{latin1_to_string, string_to_latin1},
+};
+
+/// Owner identifier of the UFID frames holding a MusicBrainz recording id.
+pub const MUSICBRAINZ_OWNER_IDENTIFIER: &str = "http://musicbrainz.org";
+pub const MAX_IDENTIFIER_LENGTH: usize = 64;
+
+/// Content of a UFID frame, which identifies the file in the database of its owner.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UniqueFileIdentifier {
+    /// URL or email address of the organisation responsible for the database
+    pub owner_identifier: String,
+    /// At most 64 bytes of binary data
+    pub identifier: Vec<u8>,
+}
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum UniqueFileIdentifierError {
+    #[error("The owner identifier is not terminated by a NUL byte")]
+    MissingData,
+    #[error("The owner identifier is empty")]
+    EmptyOwnerIdentifier,
+    #[error("The identifier should be at most 64 bytes long, but it was {0}")]
+    IdentifierTooLong(usize),
+    #[error("{0:?} is not a MusicBrainz id")]
+    InvalidMusicBrainzId(String),
+}
+
+fn is_musicbrainz_id(id: &str) -> bool {
+    id.len() == 36
+        && id.char_indices().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+impl UniqueFileIdentifier {
+    pub fn parse(bytes: &[u8]) -> Result<Self, UniqueFileIdentifierError> {
+        let Some(position) = bytes.iter().position(|byte| *byte == 0) else {
+            return Err(UniqueFileIdentifierError::MissingData);
+        };
+        let owner_identifier = latin1_to_string(&bytes[..position]);
+        let identifier = bytes[position + 1..].to_vec();
+
+        let ufid = UniqueFileIdentifier {
+            owner_identifier,
+            identifier,
+        };
+        ufid.validate()?;
+        Ok(ufid)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, UniqueFileIdentifierError> {
+        self.validate()?;
+        let mut bytes = string_to_latin1(&self.owner_identifier);
+        bytes.push(0);
+        bytes.extend_from_slice(&self.identifier);
+        Ok(bytes)
+    }
+
+    fn validate(&self) -> Result<(), UniqueFileIdentifierError> {
+        if self.owner_identifier.is_empty() {
+            return Err(UniqueFileIdentifierError::EmptyOwnerIdentifier);
+        }
+        if self.identifier.len() > MAX_IDENTIFIER_LENGTH {
+            return Err(UniqueFileIdentifierError::IdentifierTooLong(
+                self.identifier.len(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// A UFID holding a MusicBrainz recording id, which is written in lowercase.
+    pub fn musicbrainz_recording(recording_id: &str) -> Result<Self, UniqueFileIdentifierError> {
+        let recording_id = recording_id.trim();
+        if !is_musicbrainz_id(recording_id) {
+            return Err(UniqueFileIdentifierError::InvalidMusicBrainzId(
+                recording_id.to_string(),
+            ));
+        }
+        Ok(UniqueFileIdentifier {
+            owner_identifier: String::from(MUSICBRAINZ_OWNER_IDENTIFIER),
+            identifier: recording_id.to_ascii_lowercase().into_bytes(),
+        })
+    }
+
+    /// The MusicBrainz recording id, if the UFID is owned by MusicBrainz and holds a well
+    /// formed id. Some taggers add a trailing NUL, which is ignored.
+    pub fn musicbrainz_recording_id(&self) -> Option<String> {
+        if self.owner_identifier != MUSICBRAINZ_OWNER_IDENTIFIER {
+            return None;
+        }
+        let (recording_id, _) = Encoding::Latin1.read_string(&self.identifier);
+        is_musicbrainz_id(&recording_id).then(|| recording_id.to_ascii_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn musicbrainz_recording() {
+        let bytes = b"http://musicbrainz.org\x00c0b1e1d4-7b4a-4c5b-9ef2-1a6b7c3f0e42".to_vec();
+        let ufid = UniqueFileIdentifier::parse(&bytes).unwrap();
+        assert_eq!(
+            ufid.musicbrainz_recording_id(),
+            Some(String::from("c0b1e1d4-7b4a-4c5b-9ef2-1a6b7c3f0e42"))
+        );
+        assert_eq!(ufid.to_bytes(), Ok(bytes));
+        assert_eq!(
+            UniqueFileIdentifier::musicbrainz_recording("C0B1E1D4-7B4A-4C5B-9EF2-1A6B7C3F0E42"),
+            Ok(ufid)
+        );
+    }
+
+    #[test]
+    fn invalid_ufid() {
+        assert_eq!(
+            UniqueFileIdentifier::parse(b"http://example.com"),
+            Err(UniqueFileIdentifierError::MissingData)
+        );
+        assert_eq!(
+            UniqueFileIdentifier::parse(b"\x00id"),
+            Err(UniqueFileIdentifierError::EmptyOwnerIdentifier)
+        );
+        let too_long = [&b"http://example.com\x00"[..], &[b'1'; 65]].concat();
+        assert_eq!(
+            UniqueFileIdentifier::parse(&too_long),
+            Err(UniqueFileIdentifierError::IdentifierTooLong(65))
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use thiserror::Error;
+
+use super::{
+    extended_header::ExtendedHeader,
+    frame::{frame_id::FrameID, multiplicity::FrameMultiplicity, Frame},
+    header::Header,
+    Tag,
+};
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum FrameInsertError {
+    #[error("Only one {0} frame is allowed per tag")]
+    OnlyOnePerTag(FrameID),
+    #[error("The tag already has a {0} frame with the same key")]
+    DuplicateKey(FrameID),
+}
+
+impl Tag {
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn extended_header(&self) -> Option<&ExtendedHeader> {
+        self.extended_header.as_ref()
+    }
+
+    /// Every frame, in the order they appear in the tag.
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// The first frame with the given id.
+    pub fn get(&self, id: &[u8; 4]) -> Option<&Frame> {
+        self.frames
+            .iter()
+            .find(|frame| frame.frame_id().bytes() == id)
+    }
+
+    pub fn get_all(&self, id: &[u8; 4]) -> impl Iterator<Item = &Frame> {
+        let id = *id;
+        self.frames
+            .iter()
+            .filter(move |frame| frame.frame_id().bytes() == &id)
+    }
+
+    pub(super) fn multiplicity(&self, frame: &Frame) -> FrameMultiplicity {
+        let major_version = self.header.major_version();
+        let content = frame.content(major_version).unwrap_or(frame.data());
+        FrameMultiplicity::of(frame.frame_id(), content)
+    }
+
+    /// Whether the spec forbids both frames to be in the same tag.
+    fn conflicts(&self, frame: &Frame, other: &Frame) -> bool {
+        if frame.frame_id() != other.frame_id() {
+            return false;
+        }
+        self.multiplicity(frame)
+            .conflicts_with(&self.multiplicity(other))
+    }
+
+    /// Adds the frame at the end of the tag, unless the spec forbids it because of a frame
+    /// already there.
+    pub fn insert(&mut self, frame: Frame) -> Result<(), FrameInsertError> {
+        if self
+            .frames
+            .iter()
+            .any(|other| self.conflicts(&frame, other))
+        {
+            let frame_id = frame.frame_id().clone();
+            return Err(match self.multiplicity(&frame) {
+                FrameMultiplicity::OnePerTag => FrameInsertError::OnlyOnePerTag(frame_id),
+                _ => FrameInsertError::DuplicateKey(frame_id),
+            });
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    /// Adds the frame in place of the frames the spec forbids it to be with, and returns them.
+    /// When there are none, the frame is added at the end of the tag.
+    pub fn replace(&mut self, frame: Frame) -> Vec<Frame> {
+        let mut kept = vec![];
+        let mut replaced = vec![];
+        let mut position = None;
+        for other in std::mem::take(&mut self.frames) {
+            if self.conflicts(&frame, &other) {
+                position.get_or_insert(kept.len());
+                replaced.push(other);
+            } else {
+                kept.push(other);
+            }
+        }
+
+        self.frames = kept;
+        self.frames
+            .insert(position.unwrap_or(self.frames.len()), frame);
+        replaced
+    }
+
+    /// Removes every frame with the given id, and returns them.
+    pub fn remove(&mut self, id: &[u8; 4]) -> Vec<Frame> {
+        let (removed, kept) = std::mem::take(&mut self.frames)
+            .into_iter()
+            .partition(|frame| frame.frame_id().bytes() == id);
+        self.frames = kept;
+        removed
+    }
+
+    /// Keeps only the frames for which the predicate returns true.
+    pub fn retain(&mut self, predicate: impl FnMut(&Frame) -> bool) {
+        self.frames.retain(predicate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::test_utils::frame;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn uniqueness_rules() {
+        let mut tag = Tag::default();
+        tag.insert(frame(b"TIT2", b"\x00Title")).unwrap();
+        tag.insert(frame(b"COMM", b"\x00engSource\x00Vinyl"))
+            .unwrap();
+        tag.insert(frame(b"COMM", b"\x00eng\x00A comment")).unwrap();
+
+        assert_eq!(
+            tag.insert(frame(b"TIT2", b"\x00Other title")),
+            Err(FrameInsertError::OnlyOnePerTag(
+                FrameID::try_from(*b"TIT2").unwrap()
+            ))
+        );
+        assert_eq!(
+            tag.insert(frame(b"COMM", b"\x03engSource\x00CD")),
+            Err(FrameInsertError::DuplicateKey(
+                FrameID::try_from(*b"COMM").unwrap()
+            ))
+        );
+
+        let replaced = tag.replace(frame(b"COMM", b"\x03engSource\x00CD"));
+        assert_eq!(replaced, vec![frame(b"COMM", b"\x00engSource\x00Vinyl")]);
+        assert_eq!(tag.frames()[1].data(), b"\x03engSource\x00CD");
+        assert_eq!(tag.get_all(b"COMM").count(), 2);
+
+        tag.replace(frame(b"TALB", b"\x00Album"));
+        assert_eq!(tag.frames().last().unwrap().frame_id().bytes(), b"TALB");
+
+        assert_eq!(tag.remove(b"COMM").len(), 2);
+        tag.retain(|frame| frame.frame_id().bytes() != b"TALB");
+        assert_eq!(tag.frames(), &[frame(b"TIT2", b"\x00Title")]);
+        assert_eq!(tag.get(b"TIT2"), Some(&frame(b"TIT2", b"\x00Title")));
+    }
+}
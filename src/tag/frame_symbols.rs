@@ -0,0 +1,152 @@
+use super::{
+    frame::{
+        registration::{EncryptionMethodRegistration, GroupIdentificationRegistration},
+        Frame,
+    },
+    Tag,
+};
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum SymbolReference<T> {
+    Registered(T),
+    /// No registration frame in the tag declares this symbol
+    Dangling(u8),
+}
+
+impl<T> SymbolReference<T> {
+    pub fn is_dangling(&self) -> bool {
+        matches!(self, SymbolReference::Dangling(_))
+    }
+}
+
+/// The ENCR and GRID registrations referred to by a frame with the encryption or grouping flags.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FrameSymbols<'a> {
+    pub frame: &'a Frame,
+    pub encryption_method: Option<SymbolReference<EncryptionMethodRegistration>>,
+    pub group: Option<SymbolReference<GroupIdentificationRegistration>>,
+}
+
+impl FrameSymbols<'_> {
+    pub fn has_dangling_reference(&self) -> bool {
+        self.encryption_method
+            .as_ref()
+            .is_some_and(SymbolReference::is_dangling)
+            || self
+                .group
+                .as_ref()
+                .is_some_and(SymbolReference::is_dangling)
+    }
+}
+
+impl Tag {
+    /// Every well formed ENCR frame in the tag.
+    pub fn encryption_method_registrations(&self) -> Vec<EncryptionMethodRegistration> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.frame_id().bytes() == b"ENCR")
+            .filter_map(|frame| EncryptionMethodRegistration::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// Every well formed GRID frame in the tag.
+    pub fn group_identification_registrations(&self) -> Vec<GroupIdentificationRegistration> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.frame_id().bytes() == b"GRID")
+            .filter_map(|frame| GroupIdentificationRegistration::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// Resolves the method and group symbols of every encrypted or grouped frame. Frames too
+    /// short to hold the symbols their flags announce are left out.
+    pub fn frame_symbols(&self) -> Vec<FrameSymbols<'_>> {
+        let major_version = self.header.major_version();
+        let encryption_methods = self.encryption_method_registrations();
+        let groups = self.group_identification_registrations();
+
+        self.frames
+            .iter()
+            .filter_map(|frame| {
+                let flag_data = frame.flag_data(major_version).ok()?;
+                if flag_data.encryption_method.is_none() && flag_data.group_symbol.is_none() {
+                    return None;
+                }
+
+                let encryption_method = flag_data.encryption_method.map(|symbol| {
+                    encryption_methods
+                        .iter()
+                        .find(|registration| registration.method_symbol == symbol)
+                        .map(|registration| SymbolReference::Registered(registration.clone()))
+                        .unwrap_or(SymbolReference::Dangling(symbol))
+                });
+                let group = flag_data.group_symbol.map(|symbol| {
+                    groups
+                        .iter()
+                        .find(|registration| registration.group_symbol == symbol)
+                        .map(|registration| SymbolReference::Registered(registration.clone()))
+                        .unwrap_or(SymbolReference::Dangling(symbol))
+                });
+
+                Some(FrameSymbols {
+                    frame,
+                    encryption_method,
+                    group,
+                })
+            })
+            .collect()
+    }
+
+    /// Frames referring to a method or group symbol that no ENCR or GRID frame registers.
+    pub fn dangling_symbol_references(&self) -> Vec<FrameSymbols<'_>> {
+        self.frame_symbols()
+            .into_iter()
+            .filter(FrameSymbols::has_dangling_reference)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::test_utils::{flagged_frame, frame, read_back};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resolve_and_report_dangling() {
+        let tag = read_back(vec![
+            frame(b"ENCR", b"drm@example.com\x00\x81"),
+            frame(b"GRID", b"http://example.com\x00\x90"),
+            // Grouped with 0x90 and encrypted with 0x81
+            flagged_frame(b"PRIV", 0b0100_0100, b"\x90\x81secret"),
+            // Encrypted with the unregistered 0x82
+            flagged_frame(b"TIT2", 0b0000_0100, b"\x82title"),
+            frame(b"TALB", b"\x00album"),
+        ]);
+
+        let symbols = tag.frame_symbols();
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].frame.frame_id().bytes(), b"PRIV");
+        assert_eq!(
+            symbols[0].encryption_method,
+            Some(SymbolReference::Registered(EncryptionMethodRegistration {
+                owner_identifier: String::from("drm@example.com"),
+                method_symbol: 0x81,
+                encryption_data: vec![],
+            }))
+        );
+        assert!(matches!(
+            symbols[0].group,
+            Some(SymbolReference::Registered(_))
+        ));
+
+        let dangling = tag.dangling_symbol_references();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].frame.frame_id().bytes(), b"TIT2");
+        assert_eq!(
+            dangling[0].encryption_method,
+            Some(SymbolReference::Dangling(0x82))
+        );
+        assert_eq!(dangling[0].group, None);
+    }
+}
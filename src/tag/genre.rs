@@ -0,0 +1,331 @@
+/// Genres of ID3v1, with the Winamp extensions, which TCON frames may refer to by index.
+pub const ID3V1_GENRES: [&str; 192] = [
+    "Blues",
+    "Classic Rock",
+    "Country",
+    "Dance",
+    "Disco",
+    "Funk",
+    "Grunge",
+    "Hip-Hop",
+    "Jazz",
+    "Metal",
+    "New Age",
+    "Oldies",
+    "Other",
+    "Pop",
+    "R&B",
+    "Rap",
+    "Reggae",
+    "Rock",
+    "Techno",
+    "Industrial",
+    "Alternative",
+    "Ska",
+    "Death Metal",
+    "Pranks",
+    "Soundtrack",
+    "Euro-Techno",
+    "Ambient",
+    "Trip-Hop",
+    "Vocal",
+    "Jazz+Funk",
+    "Fusion",
+    "Trance",
+    "Classical",
+    "Instrumental",
+    "Acid",
+    "House",
+    "Game",
+    "Sound Clip",
+    "Gospel",
+    "Noise",
+    "AlternRock",
+    "Bass",
+    "Soul",
+    "Punk",
+    "Space",
+    "Meditative",
+    "Instrumental Pop",
+    "Instrumental Rock",
+    "Ethnic",
+    "Gothic",
+    "Darkwave",
+    "Techno-Industrial",
+    "Electronic",
+    "Pop-Folk",
+    "Eurodance",
+    "Dream",
+    "Southern Rock",
+    "Comedy",
+    "Cult",
+    "Gangsta",
+    "Top 40",
+    "Christian Rap",
+    "Pop/Funk",
+    "Jungle",
+    "Native American",
+    "Cabaret",
+    "New Wave",
+    "Psychadelic",
+    "Rave",
+    "Showtunes",
+    "Trailer",
+    "Lo-Fi",
+    "Tribal",
+    "Acid Punk",
+    "Acid Jazz",
+    "Polka",
+    "Retro",
+    "Musical",
+    "Rock & Roll",
+    "Hard Rock",
+    "Folk",
+    "Folk-Rock",
+    "National Folk",
+    "Swing",
+    "Fast Fusion",
+    "Bebob",
+    "Latin",
+    "Revival",
+    "Celtic",
+    "Bluegrass",
+    "Avantgarde",
+    "Gothic Rock",
+    "Progressive Rock",
+    "Psychedelic Rock",
+    "Symphonic Rock",
+    "Slow Rock",
+    "Big Band",
+    "Chorus",
+    "Easy Listening",
+    "Acoustic",
+    "Humour",
+    "Speech",
+    "Chanson",
+    "Opera",
+    "Chamber Music",
+    "Sonata",
+    "Symphony",
+    "Booty Bass",
+    "Primus",
+    "Porn Groove",
+    "Satire",
+    "Slow Jam",
+    "Club",
+    "Tango",
+    "Samba",
+    "Folklore",
+    "Ballad",
+    "Power Ballad",
+    "Rhythmic Soul",
+    "Freestyle",
+    "Duet",
+    "Punk Rock",
+    "Drum Solo",
+    "A capella",
+    "Euro-House",
+    "Dance Hall",
+    "Goa",
+    "Drum & Bass",
+    "Club-House",
+    "Hardcore Techno",
+    "Terror",
+    "Indie",
+    "BritPop",
+    "Negerpunk",
+    "Polsk Punk",
+    "Beat",
+    "Christian Gangsta Rap",
+    "Heavy Metal",
+    "Black Metal",
+    "Crossover",
+    "Contemporary Christian",
+    "Christian Rock",
+    "Merengue",
+    "Salsa",
+    "Thrash Metal",
+    "Anime",
+    "Jpop",
+    "Synthpop",
+    "Abstract",
+    "Art Rock",
+    "Baroque",
+    "Bhangra",
+    "Big Beat",
+    "Breakbeat",
+    "Chillout",
+    "Downtempo",
+    "Dub",
+    "EBM",
+    "Eclectic",
+    "Electro",
+    "Electroclash",
+    "Emo",
+    "Experimental",
+    "Garage",
+    "Global",
+    "IDM",
+    "Illbient",
+    "Industro-Goth",
+    "Jam Band",
+    "Krautrock",
+    "Leftfield",
+    "Lounge",
+    "Math Rock",
+    "New Romantic",
+    "Nu-Breakz",
+    "Post-Punk",
+    "Post-Rock",
+    "Psytrance",
+    "Shoegaze",
+    "Space Rock",
+    "Trop Rock",
+    "World Music",
+    "Neoclassical",
+    "Audiobook",
+    "Audio Theatre",
+    "Neue Deutsche Welle",
+    "Podcast",
+    "Indie Rock",
+    "G-Funk",
+    "Dubstep",
+    "Garage Rock",
+    "Psybient",
+];
+
+const REMIX: &str = "Remix";
+const COVER: &str = "Cover";
+
+fn resolve_reference(reference: &str) -> String {
+    match reference {
+        "RX" => String::from(REMIX),
+        "CR" => String::from(COVER),
+        _ => reference
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| ID3V1_GENRES.get(index))
+            .map(|genre| genre.to_string())
+            .unwrap_or_else(|| reference.to_string()),
+    }
+}
+
+/// Resolves the genres of the strings of a TCON frame. Handles both the ID3v2.3 form, as in
+/// "(17)(4)Eurodisco", and the ID3v2.4 one, where every genre is a string of its own and
+/// references aren't enclosed in parentheses.
+pub fn parse_content_types(strings: &[String]) -> Vec<String> {
+    let mut genres: Vec<String> = vec![];
+    for string in strings {
+        let mut rest = string.as_str();
+        let mut references = 0;
+        while let Some((reference, after)) = rest
+            .strip_prefix('(')
+            .filter(|reference| !reference.starts_with('('))
+            .and_then(|reference| reference.split_once(')'))
+        {
+            genres.push(resolve_reference(reference));
+            references += 1;
+            rest = after;
+        }
+
+        // A refinement starting with a parenthesis escapes it with a second one
+        let refinement = if rest.starts_with("((") {
+            &rest[1..]
+        } else {
+            rest
+        };
+        let refinement = refinement.trim();
+        if refinement.is_empty() {
+            continue;
+        }
+        if references == 0 {
+            genres.push(resolve_reference(refinement));
+        } else if genres.last().map(String::as_str) != Some(refinement) {
+            genres.push(refinement.to_string());
+        }
+    }
+    genres
+}
+
+/// The strings of a TCON frame holding the genres. ID3v2.3 frames hold a single string, which
+/// refers to the ID3v1 genres by index and joins the others with a slash in one refinement.
+/// That is lossy: the refinement is read back as a single genre.
+pub fn content_type_strings(genres: &[&str], major_version: u8) -> Vec<String> {
+    if major_version != 3 {
+        return genres.iter().map(|genre| genre.to_string()).collect();
+    }
+
+    let mut references = String::new();
+    let mut refinements = vec![];
+    for genre in genres {
+        match *genre {
+            REMIX => references.push_str("(RX)"),
+            COVER => references.push_str("(CR)"),
+            _ => match ID3V1_GENRES.iter().position(|known| known == genre) {
+                Some(index) => references.push_str(&format!("({index})")),
+                None => refinements.push(*genre),
+            },
+        }
+    }
+
+    let refinement = refinements.join("/");
+    // A refinement starting with a parenthesis escapes it with a second one
+    if refinement.starts_with('(') {
+        references.push('(');
+    }
+    references.push_str(&refinement);
+    if references.is_empty() {
+        return vec![];
+    }
+    vec![references]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn v23_content_type() {
+        let strings = vec![String::from("(17)(4)Eurodisco")];
+        assert_eq!(
+            parse_content_types(&strings),
+            ["Rock", "Disco", "Eurodisco"]
+        );
+        assert_eq!(parse_content_types(&[String::from("(17)Rock")]), ["Rock"]);
+        assert_eq!(
+            parse_content_types(&[String::from("((Parenthesised)")]),
+            ["(Parenthesised)"]
+        );
+        assert_eq!(
+            content_type_strings(&["Rock", "Disco", "Eurodisco"], 3),
+            strings
+        );
+
+        // The genres left after the references end up in a single refinement
+        let genres = ["Rock", "(Parenthesised)", "Eurodisco", "Remix"];
+        let strings = content_type_strings(&genres, 3);
+        assert_eq!(strings, ["(17)(RX)((Parenthesised)/Eurodisco"]);
+        assert_eq!(
+            parse_content_types(&strings),
+            ["Rock", "Remix", "(Parenthesised)/Eurodisco"]
+        );
+    }
+
+    #[test]
+    fn v24_content_type() {
+        let strings = vec![
+            String::from("17"),
+            String::from("RX"),
+            String::from("Eurodisco"),
+        ];
+        assert_eq!(
+            parse_content_types(&strings),
+            ["Rock", "Remix", "Eurodisco"]
+        );
+        assert_eq!(
+            content_type_strings(&["Rock", "Eurodisco"], 4),
+            ["Rock", "Eurodisco"]
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::io::Read;
 
 use thiserror::Error;
 
-use crate::utils::read_syncsafe_integer;
+use crate::utils::{read_syncsafe_integer, write_syncsafe_integer};
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum HeaderFlagType {
@@ -39,10 +39,34 @@ pub enum HeaderReadError {
 }
 
 impl Header {
+    /// A header of an empty tag with the given flags set.
+    pub fn new(major_version: u8, flags: &[HeaderFlagType]) -> Header {
+        Header {
+            version: major_version as u16,
+            flags_byte: flags
+                .iter()
+                .fold(0, |byte, flag| byte | flag.binary_representation()),
+            tag_size: 0,
+        }
+    }
+
+    /// The same header, for a tag of the given size.
+    pub fn with_tag_size(&self, tag_size: u32) -> Header {
+        Header {
+            tag_size,
+            ..self.clone()
+        }
+    }
+
     pub fn bytes_size(&self) -> u32 {
         10
     }
 
+    /// The 3 in ID3v2.3.0
+    pub fn major_version(&self) -> u8 {
+        (self.version & 0xFF) as u8
+    }
+
     pub fn tag_size(&self) -> u32 {
         self.tag_size
     }
@@ -51,6 +75,32 @@ impl Header {
         self.flags_byte & flag_type.binary_representation() != 0
     }
 
+    pub fn to_bytes(&self) -> [u8; 10] {
+        self.bytes_with_identifier(b"ID3")
+    }
+
+    /// The footer repeating this header at the end of the tag.
+    pub fn footer_bytes(&self) -> [u8; 10] {
+        self.bytes_with_identifier(b"3DI")
+    }
+
+    fn bytes_with_identifier(&self, identifier: &[u8; 3]) -> [u8; 10] {
+        let [version_major, version_revision] = self.version.to_le_bytes();
+        let [size_0, size_1, size_2, size_3] = write_syncsafe_integer(self.tag_size);
+        [
+            identifier[0],
+            identifier[1],
+            identifier[2],
+            version_major,
+            version_revision,
+            self.flags_byte,
+            size_0,
+            size_1,
+            size_2,
+            size_3,
+        ]
+    }
+
     pub fn read(reader: &mut impl Read) -> Result<Header, HeaderReadError> {
         let mut buffer = [0u8; 10];
         reader
@@ -109,5 +159,6 @@ mod tests {
         assert!(header.is_flag_set(&HeaderFlagType::FooterPresent));
         assert!(header.is_flag_set(&HeaderFlagType::ExtendedHeader));
         assert!(!header.is_flag_set(&HeaderFlagType::ExperimentalIndicator));
+        assert_eq!(header.to_bytes(), bytes);
     }
 }
@@ -0,0 +1,720 @@
+use std::fmt::Display;
+
+use super::{
+    encoding::{Encoding, EncodingError},
+    frame::{
+        attached_picture::{AttachedPicture, PictureType},
+        comment::Comment,
+        frame_id::FrameID,
+        text_information::TextInformation,
+        Frame,
+    },
+    genre::{content_type_strings, parse_content_types},
+    Tag,
+};
+
+/// Language written in new COMM and USLT frames, as the spec asks when it isn't known.
+const UNKNOWN_LANGUAGE: [u8; 3] = *b"XXX";
+
+/// Position of a track in an album, or of a disc in a set, as stored in TRCK and TPOS frames.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SetPosition {
+    pub number: u32,
+    pub total: Option<u32>,
+}
+
+impl SetPosition {
+    fn parse(string: &str) -> Option<Self> {
+        let (number, total) = match string.split_once('/') {
+            Some((number, total)) => (number, Some(total)),
+            None => (string, None),
+        };
+        Some(SetPosition {
+            number: number.trim().parse().ok()?,
+            total: total.and_then(|total| total.trim().parse().ok()),
+        })
+    }
+}
+
+impl Display for SetPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{}/{}", self.number, total),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+/// Recording date, which ID3v2.4 stores in TDRC and ID3v2.3 splits between TYER and TDAT.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct RecordingDate {
+    pub year: u16,
+    pub month: Option<u8>,
+    /// Only kept when the month is known
+    pub day: Option<u8>,
+}
+
+impl RecordingDate {
+    /// Parses the beginning of an ID3v2.4 timestamp, as in "2004-05-17T12:00".
+    fn parse(timestamp: &str) -> Option<Self> {
+        let mut parts = timestamp.trim().split(['-', 'T']);
+        let year = parts.next()?.parse().ok()?;
+        let month = parts
+            .next()
+            .and_then(|month| month.parse().ok())
+            .filter(|month| (1..=12).contains(month));
+        let day = parts
+            .next()
+            .and_then(|day| day.parse().ok())
+            .filter(|day| (1..=31).contains(day) && month.is_some());
+        Some(RecordingDate { year, month, day })
+    }
+}
+
+impl Display for RecordingDate {
+    /// Formats the date as an ID3v2.4 timestamp.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{month:02}")?;
+            if let Some(day) = self.day {
+                write!(f, "-{day:02}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Plain getters and setters for the metadata most applications deal with, which read from and
+/// write to the right frames for the version of the tag. Setting `None`, or an empty list,
+/// removes the frames.
+pub trait Metadata {
+    fn title(&self) -> Option<String>;
+    fn set_title(&mut self, title: Option<&str>) -> Result<(), EncodingError>;
+
+    /// The first artist.
+    fn artist(&self) -> Option<String>;
+    fn set_artist(&mut self, artist: Option<&str>) -> Result<(), EncodingError>;
+    /// ID3v2.3 frames separate the artists with a slash.
+    fn artists(&self) -> Vec<String>;
+    fn set_artists(&mut self, artists: &[&str]) -> Result<(), EncodingError>;
+
+    fn album(&self) -> Option<String>;
+    fn set_album(&mut self, album: Option<&str>) -> Result<(), EncodingError>;
+    fn album_artist(&self) -> Option<String>;
+    fn set_album_artist(&mut self, album_artist: Option<&str>) -> Result<(), EncodingError>;
+
+    fn track(&self) -> Option<SetPosition>;
+    fn set_track(&mut self, track: Option<SetPosition>) -> Result<(), EncodingError>;
+    fn disc(&self) -> Option<SetPosition>;
+    fn set_disc(&mut self, disc: Option<SetPosition>) -> Result<(), EncodingError>;
+
+    fn year(&self) -> Option<u16>;
+    fn set_year(&mut self, year: Option<u16>) -> Result<(), EncodingError>;
+    fn date(&self) -> Option<RecordingDate>;
+    fn set_date(&mut self, date: Option<RecordingDate>) -> Result<(), EncodingError>;
+
+    /// The first genre.
+    fn genre(&self) -> Option<String>;
+    fn set_genre(&mut self, genre: Option<&str>) -> Result<(), EncodingError>;
+    /// Genres referred to by their ID3v1 index are resolved to their name.
+    fn genres(&self) -> Vec<String>;
+    fn set_genres(&mut self, genres: &[&str]) -> Result<(), EncodingError>;
+
+    /// The text of the comment without a description. Comments with a description are usually
+    /// written by applications for their own use.
+    fn comment(&self) -> Option<String>;
+    fn set_comment(&mut self, comment: Option<&str>) -> Result<(), EncodingError>;
+    /// The lyrics without a description, or the first lyrics if they all have one.
+    fn lyrics(&self) -> Option<String>;
+    fn set_lyrics(&mut self, lyrics: Option<&str>) -> Result<(), EncodingError>;
+
+    /// The front cover, or the first picture if there is none.
+    fn cover(&self) -> Option<AttachedPicture>;
+    /// Replaces the pictures with the same picture type.
+    fn set_cover(&mut self, cover: Option<AttachedPicture>);
+
+    fn composer(&self) -> Option<String>;
+    fn set_composer(&mut self, composer: Option<&str>) -> Result<(), EncodingError>;
+    fn bpm(&self) -> Option<u32>;
+    fn set_bpm(&mut self, bpm: Option<u32>);
+    /// Whether the album is a compilation, as told by the iTunes TCMP frame.
+    fn compilation(&self) -> bool;
+    fn set_compilation(&mut self, compilation: bool) -> Result<(), EncodingError>;
+
+    fn title_sort_order(&self) -> Option<String>;
+    fn set_title_sort_order(&mut self, title: Option<&str>) -> Result<(), EncodingError>;
+    fn artist_sort_order(&self) -> Option<String>;
+    fn set_artist_sort_order(&mut self, artist: Option<&str>) -> Result<(), EncodingError>;
+    fn album_sort_order(&self) -> Option<String>;
+    fn set_album_sort_order(&mut self, album: Option<&str>) -> Result<(), EncodingError>;
+    fn album_artist_sort_order(&self) -> Option<String>;
+    fn set_album_artist_sort_order(
+        &mut self,
+        album_artist: Option<&str>,
+    ) -> Result<(), EncodingError>;
+    fn composer_sort_order(&self) -> Option<String>;
+    fn set_composer_sort_order(&mut self, composer: Option<&str>) -> Result<(), EncodingError>;
+}
+
+fn frame_id(id: &[u8; 4]) -> FrameID {
+    FrameID::try_from(*id).unwrap()
+}
+
+/// ID3v2.4 sort order frames, and the ones iTunes writes in ID3v2.3 tags in their place.
+fn sort_order_frame_ids(id: &[u8; 4], major_version: u8) -> [&[u8; 4]; 2] {
+    let v23_id = match id {
+        b"TSOT" => b"XSOT",
+        b"TSOP" => b"XSOP",
+        b"TSOA" => b"XSOA",
+        id => id,
+    };
+    if major_version == 3 {
+        [v23_id, id]
+    } else {
+        [id, v23_id]
+    }
+}
+
+impl Tag {
+    /// The encoding [`Encoding::select`] picks for the strings, within the tag restrictions.
+    pub(super) fn text_encoding(&self, strings: &[&str]) -> Result<Encoding, EncodingError> {
+        let major_version = self.header.major_version();
+        let restrictions = self
+            .extended_header
+            .as_ref()
+            .and_then(|xheader| xheader.restrictions())
+            .map(|restrictions| &restrictions.text_encoding_restrictions);
+        Encoding::select(major_version, restrictions, strings)
+    }
+
+    fn contents(&self, id: &[u8; 4]) -> impl Iterator<Item = &[u8]> {
+        let major_version = self.header.major_version();
+        let id = *id;
+        self.frames
+            .iter()
+            .filter(move |frame| frame.frame_id().bytes() == &id)
+            .filter_map(move |frame| frame.content(major_version).ok())
+    }
+
+    /// Replaces the frames with the given id whose content matches, keeping the position of the
+    /// first one.
+    fn replace_frames(
+        &mut self,
+        id: &[u8; 4],
+        matches: impl Fn(&[u8]) -> bool,
+        data: Option<Vec<u8>>,
+    ) {
+        let major_version = self.header.major_version();
+        let is_replaced = |frame: &Frame| {
+            frame.frame_id().bytes() == id && frame.content(major_version).is_ok_and(&matches)
+        };
+
+        let position = self.frames.iter().position(is_replaced);
+        self.frames.retain(|frame| !is_replaced(frame));
+        if let Some(data) = data {
+            let frame = Frame::new(frame_id(id), data);
+            match position {
+                Some(position) => self.frames.insert(position, frame),
+                None => self.frames.push(frame),
+            }
+        }
+    }
+
+    fn text_strings(&self, id: &[u8; 4]) -> Vec<String> {
+        self.contents(id)
+            .find_map(|content| TextInformation::parse(content).ok())
+            .map(|text| text.strings().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn text(&self, id: &[u8; 4]) -> Option<String> {
+        self.text_strings(id)
+            .into_iter()
+            .find(|string| !string.is_empty())
+    }
+
+    /// ID3v2.3 frames hold a single string, so the strings are joined with a slash.
+    fn set_text_strings(
+        &mut self,
+        id: &[u8; 4],
+        mut strings: Vec<String>,
+    ) -> Result<(), EncodingError> {
+        if self.header.major_version() == 3 && strings.len() > 1 {
+            strings = vec![strings.join("/")];
+        }
+        let data = if strings.is_empty() {
+            None
+        } else {
+            let encoding =
+                self.text_encoding(&strings.iter().map(String::as_str).collect::<Vec<&str>>())?;
+            Some(TextInformation::new(encoding, strings).to_bytes())
+        };
+        self.replace_frames(id, |_| true, data);
+        Ok(())
+    }
+
+    fn set_text(&mut self, id: &[u8; 4], text: Option<&str>) -> Result<(), EncodingError> {
+        self.set_text_strings(id, text.map(str::to_string).into_iter().collect())
+    }
+
+    /// ID3v2.3 frames hold a single string, where the values are separated by a slash.
+    fn text_list(&self, id: &[u8; 4]) -> Vec<String> {
+        let strings = self.text_strings(id);
+        if self.header.major_version() != 3 {
+            return strings;
+        }
+        strings
+            .iter()
+            .flat_map(|string| string.split('/'))
+            .map(str::to_string)
+            .filter(|string| !string.is_empty())
+            .collect()
+    }
+
+    fn set_text_list(&mut self, id: &[u8; 4], values: &[&str]) -> Result<(), EncodingError> {
+        self.set_text_strings(id, values.iter().map(|value| value.to_string()).collect())
+    }
+
+    fn sort_order(&self, id: &[u8; 4]) -> Option<String> {
+        sort_order_frame_ids(id, self.header.major_version())
+            .into_iter()
+            .find_map(|id| self.text(id))
+    }
+
+    fn set_sort_order(&mut self, id: &[u8; 4], text: Option<&str>) -> Result<(), EncodingError> {
+        let [id, other_id] = sort_order_frame_ids(id, self.header.major_version());
+        // Checked before the other frame is removed, so that a failure leaves the tag unchanged
+        let encoding = text.map(|text| self.text_encoding(&[text])).transpose()?;
+        if id != other_id {
+            self.set_text(other_id, None)?;
+        }
+        self.replace_frames(
+            id,
+            |_| true,
+            text.zip(encoding).map(|(text, encoding)| {
+                TextInformation::new(encoding, vec![text.to_string()]).to_bytes()
+            }),
+        );
+        Ok(())
+    }
+
+    fn comments(&self, id: &[u8; 4]) -> Vec<Comment> {
+        self.contents(id)
+            .filter_map(|content| Comment::parse(content).ok())
+            .collect()
+    }
+
+    /// Replaces the frames without description, whatever their language.
+    fn set_comment_text(&mut self, id: &[u8; 4], text: Option<&str>) -> Result<(), EncodingError> {
+        let data = match text {
+            Some(text) => Some(
+                Comment {
+                    encoding: self.text_encoding(&[text])?,
+                    language: UNKNOWN_LANGUAGE,
+                    description: String::new(),
+                    text: text.to_string(),
+                }
+                .to_bytes(),
+            ),
+            None => None,
+        };
+        self.replace_frames(
+            id,
+            |content| Comment::parse(content).is_ok_and(|comment| comment.description.is_empty()),
+            data,
+        );
+        Ok(())
+    }
+}
+
+impl Metadata for Tag {
+    fn title(&self) -> Option<String> {
+        self.text(b"TIT2")
+    }
+
+    fn set_title(&mut self, title: Option<&str>) -> Result<(), EncodingError> {
+        self.set_text(b"TIT2", title)
+    }
+
+    fn artist(&self) -> Option<String> {
+        self.artists().into_iter().next()
+    }
+
+    fn set_artist(&mut self, artist: Option<&str>) -> Result<(), EncodingError> {
+        self.set_artists(&artist.into_iter().collect::<Vec<&str>>())
+    }
+
+    fn artists(&self) -> Vec<String> {
+        self.text_list(b"TPE1")
+    }
+
+    fn set_artists(&mut self, artists: &[&str]) -> Result<(), EncodingError> {
+        self.set_text_list(b"TPE1", artists)
+    }
+
+    fn album(&self) -> Option<String> {
+        self.text(b"TALB")
+    }
+
+    fn set_album(&mut self, album: Option<&str>) -> Result<(), EncodingError> {
+        self.set_text(b"TALB", album)
+    }
+
+    fn album_artist(&self) -> Option<String> {
+        self.text(b"TPE2")
+    }
+
+    fn set_album_artist(&mut self, album_artist: Option<&str>) -> Result<(), EncodingError> {
+        self.set_text(b"TPE2", album_artist)
+    }
+
+    fn track(&self) -> Option<SetPosition> {
+        self.text(b"TRCK")
+            .and_then(|track| SetPosition::parse(&track))
+    }
+
+    fn set_track(&mut self, track: Option<SetPosition>) -> Result<(), EncodingError> {
+        self.set_text(b"TRCK", track.map(|track| track.to_string()).as_deref())
+    }
+
+    fn disc(&self) -> Option<SetPosition> {
+        self.text(b"TPOS")
+            .and_then(|disc| SetPosition::parse(&disc))
+    }
+
+    fn set_disc(&mut self, disc: Option<SetPosition>) -> Result<(), EncodingError> {
+        self.set_text(b"TPOS", disc.map(|disc| disc.to_string()).as_deref())
+    }
+
+    fn year(&self) -> Option<u16> {
+        self.date().map(|date| date.year)
+    }
+
+    fn set_year(&mut self, year: Option<u16>) -> Result<(), EncodingError> {
+        self.set_date(year.map(|year| RecordingDate {
+            year,
+            month: None,
+            day: None,
+        }))
+    }
+
+    fn date(&self) -> Option<RecordingDate> {
+        let v24_date = || {
+            self.text(b"TDRC")
+                .and_then(|timestamp| RecordingDate::parse(&timestamp))
+        };
+        let v23_date = || {
+            let year = self.text(b"TYER")?.trim().parse().ok()?;
+            // TDAT holds the day, then the month, as in "1705"
+            let day_month = self
+                .text(b"TDAT")
+                .filter(|date| date.len() == 4 && date.bytes().all(|byte| byte.is_ascii_digit()));
+            let (day, month) = match day_month {
+                Some(date) => (date[..2].parse().ok(), date[2..].parse().ok()),
+                None => (None, None),
+            };
+            Some(RecordingDate { year, month, day })
+        };
+
+        if self.header.major_version() == 3 {
+            v23_date().or_else(v24_date)
+        } else {
+            v24_date().or_else(v23_date)
+        }
+    }
+
+    fn set_date(&mut self, date: Option<RecordingDate>) -> Result<(), EncodingError> {
+        if self.header.major_version() != 3 {
+            self.set_text(b"TYER", None)?;
+            self.set_text(b"TDAT", None)?;
+            return self.set_text(b"TDRC", date.map(|date| date.to_string()).as_deref());
+        }
+
+        let day_month = date.and_then(|date| match (date.day, date.month) {
+            (Some(day), Some(month)) => Some(format!("{day:02}{month:02}")),
+            _ => None,
+        });
+        self.set_text(b"TDRC", None)?;
+        self.set_text(
+            b"TYER",
+            date.map(|date| format!("{:04}", date.year)).as_deref(),
+        )?;
+        self.set_text(b"TDAT", day_month.as_deref())
+    }
+
+    fn genre(&self) -> Option<String> {
+        self.genres().into_iter().next()
+    }
+
+    fn set_genre(&mut self, genre: Option<&str>) -> Result<(), EncodingError> {
+        self.set_genres(&genre.into_iter().collect::<Vec<&str>>())
+    }
+
+    fn genres(&self) -> Vec<String> {
+        parse_content_types(&self.text_strings(b"TCON"))
+    }
+
+    fn set_genres(&mut self, genres: &[&str]) -> Result<(), EncodingError> {
+        let strings = content_type_strings(genres, self.header.major_version());
+        self.set_text_strings(b"TCON", strings)
+    }
+
+    fn comment(&self) -> Option<String> {
+        self.comments(b"COMM")
+            .into_iter()
+            .find(|comment| comment.description.is_empty())
+            .map(|comment| comment.text)
+    }
+
+    fn set_comment(&mut self, comment: Option<&str>) -> Result<(), EncodingError> {
+        self.set_comment_text(b"COMM", comment)
+    }
+
+    fn lyrics(&self) -> Option<String> {
+        let lyrics = self.comments(b"USLT");
+        lyrics
+            .iter()
+            .find(|lyrics| lyrics.description.is_empty())
+            .or(lyrics.first())
+            .map(|lyrics| lyrics.text.clone())
+    }
+
+    fn set_lyrics(&mut self, lyrics: Option<&str>) -> Result<(), EncodingError> {
+        self.set_comment_text(b"USLT", lyrics)
+    }
+
+    fn cover(&self) -> Option<AttachedPicture> {
+        let pictures = self
+            .contents(b"APIC")
+            .filter_map(|content| AttachedPicture::parse(content).ok())
+            .collect::<Vec<AttachedPicture>>();
+        pictures
+            .iter()
+            .find(|picture| picture.picture_type == PictureType::FrontCover)
+            .or(pictures.first())
+            .cloned()
+    }
+
+    fn set_cover(&mut self, cover: Option<AttachedPicture>) {
+        let picture_type = cover
+            .as_ref()
+            .map(|cover| cover.picture_type)
+            .unwrap_or(PictureType::FrontCover);
+        self.replace_frames(
+            b"APIC",
+            |content| {
+                AttachedPicture::parse(content)
+                    .is_ok_and(|picture| picture.picture_type == picture_type)
+            },
+            cover.map(|cover| cover.to_bytes()),
+        );
+    }
+
+    fn composer(&self) -> Option<String> {
+        self.text(b"TCOM")
+    }
+
+    fn set_composer(&mut self, composer: Option<&str>) -> Result<(), EncodingError> {
+        self.set_text(b"TCOM", composer)
+    }
+
+    fn bpm(&self) -> Option<u32> {
+        self.contents(b"TBPM")
+            .find_map(|content| TextInformation::parse(content).ok())
+            .and_then(|text| text.bpm().ok())
+    }
+
+    fn set_bpm(&mut self, bpm: Option<u32>) {
+        let data = bpm.map(|bpm| TextInformation::from_bpm(bpm).to_bytes());
+        self.replace_frames(b"TBPM", |_| true, data);
+    }
+
+    fn compilation(&self) -> bool {
+        self.text(b"TCMP").is_some_and(|value| value.trim() == "1")
+    }
+
+    fn set_compilation(&mut self, compilation: bool) -> Result<(), EncodingError> {
+        self.set_text(b"TCMP", compilation.then_some("1"))
+    }
+
+    fn title_sort_order(&self) -> Option<String> {
+        self.sort_order(b"TSOT")
+    }
+
+    fn set_title_sort_order(&mut self, title: Option<&str>) -> Result<(), EncodingError> {
+        self.set_sort_order(b"TSOT", title)
+    }
+
+    fn artist_sort_order(&self) -> Option<String> {
+        self.sort_order(b"TSOP")
+    }
+
+    fn set_artist_sort_order(&mut self, artist: Option<&str>) -> Result<(), EncodingError> {
+        self.set_sort_order(b"TSOP", artist)
+    }
+
+    fn album_sort_order(&self) -> Option<String> {
+        self.sort_order(b"TSOA")
+    }
+
+    fn set_album_sort_order(&mut self, album: Option<&str>) -> Result<(), EncodingError> {
+        self.set_sort_order(b"TSOA", album)
+    }
+
+    fn album_artist_sort_order(&self) -> Option<String> {
+        self.sort_order(b"TSO2")
+    }
+
+    fn set_album_artist_sort_order(
+        &mut self,
+        album_artist: Option<&str>,
+    ) -> Result<(), EncodingError> {
+        self.set_sort_order(b"TSO2", album_artist)
+    }
+
+    fn composer_sort_order(&self) -> Option<String> {
+        self.sort_order(b"TSOC")
+    }
+
+    fn set_composer_sort_order(&mut self, composer: Option<&str>) -> Result<(), EncodingError> {
+        self.set_sort_order(b"TSOC", composer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{
+        builder::TagBuilder,
+        extended_header::{TagRestrictions, TextEncodingRestrictions},
+        header::Header,
+        test_utils::frame,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_v23_tag() {
+        let bytes = TagBuilder::new()
+            .version(3)
+            .frame(frame(b"TIT2", b"\x00Title"))
+            .frame(frame(b"TPE1", b"\x00First/Second"))
+            .frame(frame(b"TRCK", b"\x003/12"))
+            .frame(frame(b"TYER", b"\x001999"))
+            .frame(frame(b"TDAT", b"\x001705"))
+            .frame(frame(b"TCON", b"\x00(17)Rock"))
+            .frame(frame(b"COMM", b"\x00engiTunNORM\x00 0000"))
+            .frame(frame(b"COMM", b"\x00eng\x00A comment"))
+            .frame(frame(b"XSOP", b"\x00Second, First"))
+            .build()
+            .unwrap()
+            .to_bytes();
+        let tag = Tag::read(&mut Cursor::new(bytes)).unwrap();
+
+        assert_eq!(tag.title(), Some(String::from("Title")));
+        assert_eq!(tag.artists(), ["First", "Second"]);
+        assert_eq!(
+            tag.track(),
+            Some(SetPosition {
+                number: 3,
+                total: Some(12)
+            })
+        );
+        assert_eq!(
+            tag.date(),
+            Some(RecordingDate {
+                year: 1999,
+                month: Some(5),
+                day: Some(17)
+            })
+        );
+        assert_eq!(tag.genres(), ["Rock"]);
+        assert_eq!(tag.comment(), Some(String::from("A comment")));
+        assert_eq!(tag.artist_sort_order(), Some(String::from("Second, First")));
+        assert_eq!(tag.album(), None);
+        assert!(!tag.compilation());
+    }
+
+    #[test]
+    fn write_v24_frames() {
+        let mut tag = Tag::default();
+        tag.set_title(Some("Titre")).unwrap();
+        tag.set_artists(&["Première", "Second"]).unwrap();
+        tag.set_date(Some(RecordingDate {
+            year: 2004,
+            month: Some(5),
+            day: None,
+        }))
+        .unwrap();
+        tag.set_genres(&["Rock", "Eurodisco"]).unwrap();
+        tag.set_bpm(Some(128));
+        tag.set_compilation(true).unwrap();
+        tag.set_comment(Some("日本語")).unwrap();
+        tag.set_title(Some("Title")).unwrap();
+
+        let frames = tag
+            .frames
+            .iter()
+            .map(|frame| (frame.frame_id().to_string(), frame.data()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            frames,
+            vec![
+                (String::from("TIT2"), &b"\x00Title"[..]),
+                (String::from("TPE1"), b"\x00Premi\xE8re\x00Second"),
+                (String::from("TDRC"), b"\x002004-05"),
+                (String::from("TCON"), b"\x00Rock\x00Eurodisco"),
+                (String::from("TBPM"), b"\x00128"),
+                (String::from("TCMP"), b"\x001"),
+                (
+                    String::from("COMM"),
+                    b"\x03XXX\x00\xE6\x97\xA5\xE6\x9C\xAC\xE8\xAA\x9E"
+                ),
+            ]
+        );
+        assert_eq!(tag.comment(), Some(String::from("日本語")));
+
+        tag.set_artists(&[]).unwrap();
+        tag.set_compilation(false).unwrap();
+        assert_eq!(tag.artist(), None);
+        assert_eq!(tag.frames.len(), 5);
+    }
+
+    #[test]
+    fn write_v23_frames() {
+        let mut tag = TagBuilder::new().version(3).build().unwrap();
+        tag.set_artists(&["First", "Second"]).unwrap();
+        tag.set_genres(&["Rock", "Eurodisco", "Italo"]).unwrap();
+        tag.set_text_strings(b"TCOM", vec![String::from("A"), String::from("B")])
+            .unwrap();
+
+        assert_eq!(tag.get(b"TPE1").unwrap().data(), b"\x00First/Second");
+        assert_eq!(tag.get(b"TCON").unwrap().data(), b"\x00(17)Eurodisco/Italo");
+        assert_eq!(tag.get(b"TCOM").unwrap().data(), b"\x00A/B");
+        assert_eq!(tag.artists(), ["First", "Second"]);
+    }
+
+    #[test]
+    fn forbidden_encoding() {
+        let mut tag = TagBuilder::new()
+            .restrictions(TagRestrictions {
+                text_encoding_restrictions: TextEncodingRestrictions::ISO88591OrUTF8,
+                ..TagRestrictions::default()
+            })
+            .frame(frame(b"TIT2", b"\x00Title"))
+            .build()
+            .unwrap();
+        // ID3v2.3 has no UTF-8 encoding to fall back on
+        tag.header = Header::new(3, &[]);
+
+        assert_eq!(
+            tag.set_title(Some("日本語")),
+            Err(EncodingError::ForbiddenEncoding(3))
+        );
+        assert_eq!(tag.title(), Some(String::from("Title")));
+        tag.set_title(Some("Titre")).unwrap();
+        assert_eq!(tag.title(), Some(String::from("Titre")));
+    }
+}
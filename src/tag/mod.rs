@@ -5,21 +5,41 @@ use thiserror::Error;
 use crate::tag::header::HeaderFlagType;
 
 use self::{
+    decryption::FrameDecryptionError,
     extended_header::{ExtendedHeader, ExtendedHeaderReadError},
-    frame::{Frame, FrameReadError},
+    frame::{unique_file_identifier::UniqueFileIdentifier, Frame, FrameReadError},
     header::{Header, HeaderReadError},
+    read_options::ReadOptions,
 };
 
-mod encoding;
-mod extended_header;
-mod frame;
-mod header;
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod builder;
+pub mod charset;
+pub mod decryption;
+pub mod encoding;
+pub mod extended_header;
+pub mod frame;
+pub mod frame_access;
+pub mod frame_symbols;
+pub mod genre;
+pub mod header;
+pub mod metadata;
+pub mod next_tag;
+pub mod read_options;
+pub mod scanner;
+pub mod signing;
+pub mod tag_ref;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 #[derive(PartialEq, Clone, Debug, Default)]
 pub struct Tag {
     header: Header,
     extended_header: Option<ExtendedHeader>,
     frames: Vec<Frame>,
+    /// in bytes, between the last frame and the footer or the end of the tag
+    padding: u32,
     footer: Option<Box<Tag>>,
 }
 
@@ -31,6 +51,12 @@ pub enum TagReadError {
     ExtendedHeaderError(ExtendedHeaderReadError),
     #[error("Error while parsing frame {0}: {1}")]
     FrameReadError(u32, FrameReadError),
+    #[error("Error while decrypting frame {0}: {1}")]
+    FrameDecryptionError(u32, FrameDecryptionError),
+    #[error("Could not seek to the next tag at byte {0}")]
+    NextTagNotReachable(u64),
+    #[error("Error while looking for the tag: {0}")]
+    ScanError(std::io::ErrorKind),
 }
 
 impl From<HeaderReadError> for TagReadError {
@@ -46,51 +72,120 @@ impl From<ExtendedHeaderReadError> for TagReadError {
 }
 
 impl Tag {
-    pub fn read(reader: &mut impl Read) -> Result<Tag, TagReadError> {
-        let header = Header::read(reader)?;
-
-        let extended_header = if header.is_flag_set(&HeaderFlagType::ExtendedHeader) {
-            Some(ExtendedHeader::read(reader)?)
+    /// in bytes, from the start of the header to the end of the footer, if present
+    pub fn bytes_size(&self) -> u32 {
+        let footer_size = if self.header.is_flag_set(&HeaderFlagType::FooterPresent) {
+            10
         } else {
-            None
+            0
         };
+        self.header.bytes_size() + self.header.tag_size() + footer_size
+    }
 
-        let footer = if header.is_flag_set(&HeaderFlagType::ExtendedHeader) {
-            todo!();
-            #[allow(unreachable_code)]
-            Some(Box::from(Tag::read(reader)?))
-        } else {
-            None
-        };
+    /// Every well formed UFID frame in the tag.
+    pub fn unique_file_identifiers(&self) -> Vec<UniqueFileIdentifier> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.frame_id().bytes() == b"UFID")
+            .filter_map(|frame| UniqueFileIdentifier::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// The recording id of the first UFID frame owned by MusicBrainz.
+    pub fn musicbrainz_recording_id(&self) -> Option<String> {
+        self.unique_file_identifiers()
+            .iter()
+            .find_map(UniqueFileIdentifier::musicbrainz_recording_id)
+    }
+
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// The whole tag as it is stored in a file. The tag size and the CRC are computed from the
+    /// frames and padding, so they stay correct after frames are changed. ID3v2.3 leaves the
+    /// padding out of the CRC.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let major_version = self.header.major_version();
+        let mut frames_and_padding: Vec<u8> = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.to_bytes(major_version))
+            .collect();
+        let frames_size = frames_and_padding.len();
+        frames_and_padding.resize(frames_size + self.padding as usize, 0);
 
-        let mut frames: Vec<Frame> = vec![];
-        let mut bytes_read = header.bytes_size()
-            + extended_header
-                .as_ref()
-                .map(|xheader| xheader.bytes_size())
-                .unwrap_or(0);
-
-        while bytes_read < header.tag_size() {
-            let new_frame = match Frame::read(reader) {
-                Err(FrameReadError::InvalidFrameID(_)) => break,
-                Err(frame_read_error) => {
-                    return Err(TagReadError::FrameReadError(
-                        frames.len() as u32 + 1,
-                        frame_read_error,
-                    ));
+        let extended_header_bytes = match &self.extended_header {
+            Some(extended_header) => {
+                let mut extended_header = extended_header.with_padding_size(self.padding);
+                if extended_header.crc().is_some() {
+                    let crc = if major_version == 3 {
+                        crc32fast::hash(&frames_and_padding[..frames_size])
+                    } else {
+                        crc32fast::hash(&frames_and_padding)
+                    };
+                    extended_header = extended_header.with_crc(crc);
                 }
-                Ok(frame) => frame,
-            };
+                extended_header.to_bytes(major_version)
+            }
+            None => vec![],
+        };
+
+        let tag_size = (extended_header_bytes.len() + frames_and_padding.len()) as u32;
+        let header = self.header.with_tag_size(tag_size);
 
-            bytes_read += new_frame.bytes_size();
-            frames.push(new_frame);
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend(extended_header_bytes);
+        bytes.extend(frames_and_padding);
+        if header.is_flag_set(&HeaderFlagType::FooterPresent) {
+            bytes.extend_from_slice(&header.footer_bytes());
         }
+        bytes
+    }
+
+    pub fn read(reader: &mut impl Read) -> Result<Tag, TagReadError> {
+        Tag::read_with_options(reader, ReadOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+    use crate::tag::{
+        builder::TagBuilder,
+        frame::{frame_reader::FrameReader, frame_ref::FrameRef},
+        read_options::ReadOptions,
+        tag_ref::TagRef,
+        test_utils::frame,
+    };
+
+    // Tags are parsed on worker threads and handed over to others
+    assert_impl_all!(Tag: Send, Sync);
+    assert_impl_all!(Frame: Send, Sync);
+    assert_impl_all!(TagRef<'static>: Send, Sync);
+    assert_impl_all!(FrameRef<'static>: Send, Sync);
+    assert_impl_all!(TagReadError: Send, Sync);
+    assert_impl_all!(ReadOptions: Send, Sync);
+    assert_impl_all!(FrameReader<std::fs::File>: Send, Sync);
+
+    #[test]
+    fn frames_up_to_footer() {
+        // The tag size includes the extended header, but not the header, and the footer follows
+        let tag = TagBuilder::new()
+            .crc(true)
+            .footer(true)
+            .frame(frame(b"TIT2", b"\x03Title"))
+            .frame(frame(b"TPE1", b"\x03Artist"))
+            .build()
+            .unwrap();
+        let bytes = [tag.to_bytes(), b"audio".to_vec()].concat();
 
-        Ok(Tag {
-            header,
-            extended_header,
-            footer,
-            frames,
-        })
+        let mut reader = &bytes[..];
+        let read_tag = Tag::read(&mut reader).unwrap();
+        assert_eq!(read_tag.frames(), tag.frames());
+        assert_eq!(read_tag.padding(), 0);
+        assert_eq!(reader, b"audio");
     }
 }
@@ -0,0 +1,289 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{
+    frame::{
+        frame_id::FrameID, multiplicity::FrameMultiplicity,
+        recommended_buffer_size::RecommendedBufferSize, seek,
+    },
+    header::HeaderReadError,
+    Tag, TagReadError,
+};
+
+/// in bytes, scanned for the next tag past the minimum offset of a SEEK frame
+const NEXT_TAG_SCAN_LIMIT: u64 = 1024 * 1024;
+
+/// Where the next tag is, from the end of the tag.
+enum NextTagOffset {
+    /// SEEK only tells the next tag isn't closer
+    Minimum(u32),
+    /// RBUF tells exactly where it is
+    Exact(u32),
+}
+
+impl Tag {
+    /// Offset in bytes from the end of this tag to the next one, as told by a SEEK or RBUF frame.
+    /// A SEEK frame only gives the minimum offset, the next tag may be further.
+    pub fn offset_to_next_tag(&self) -> Option<u32> {
+        self.next_tag_offset().map(|offset| match offset {
+            NextTagOffset::Minimum(offset) | NextTagOffset::Exact(offset) => offset,
+        })
+    }
+
+    fn next_tag_offset(&self) -> Option<NextTagOffset> {
+        let major_version = self.header.major_version();
+        self.frames.iter().find_map(|frame| {
+            let content = frame.content(major_version).ok()?;
+            match frame.frame_id().bytes() {
+                b"SEEK" => seek::Seek::parse(content)
+                    .ok()
+                    .map(|seek| NextTagOffset::Minimum(seek.minimum_offset_to_next_tag)),
+                b"RBUF" => RecommendedBufferSize::parse(content)
+                    .ok()
+                    .and_then(|rbuf| rbuf.offset_to_next_tag)
+                    .map(NextTagOffset::Exact),
+                _ => None,
+            }
+        })
+    }
+
+    /// Reads a tag, then follows the offsets of its SEEK and RBUF frames to read the tags found
+    /// later in the stream, merging them all with [`Tag::merge`]. Past the minimum offset of a
+    /// SEEK frame, the next tag is looked for in the following MiB. Following stops at the first
+    /// offset that doesn't lead to a tag.
+    pub fn read_following_offsets(reader: &mut (impl Read + Seek)) -> Result<Tag, TagReadError> {
+        let mut tag_start = reader
+            .stream_position()
+            .map_err(|_| TagReadError::NextTagNotReachable(0))?;
+        let mut tag = Tag::read(reader)?;
+        let mut next_offset = tag.next_tag_offset();
+        let mut tag_size = tag.bytes_size();
+
+        while let Some(offset) = next_offset {
+            let tag_end = tag_start + tag_size as u64;
+            let mut next_tag_start = match offset {
+                NextTagOffset::Minimum(offset) | NextTagOffset::Exact(offset) => {
+                    tag_end + offset as u64
+                }
+            };
+            reader
+                .seek(SeekFrom::Start(next_tag_start))
+                .map_err(|_| TagReadError::NextTagNotReachable(next_tag_start))?;
+            if let NextTagOffset::Minimum(_) = offset {
+                match Tag::find_within(reader, NEXT_TAG_SCAN_LIMIT) {
+                    Ok(Some(position)) => next_tag_start = position,
+                    Ok(None) => break,
+                    Err(_) => return Err(TagReadError::NextTagNotReachable(next_tag_start)),
+                }
+            }
+
+            let next_tag = match Tag::read(reader) {
+                Err(TagReadError::HeaderError(
+                    HeaderReadError::ID3NotFound | HeaderReadError::NotEnoughBytes,
+                )) => break,
+                result => result?,
+            };
+
+            tag_start = next_tag_start;
+            tag_size = next_tag.bytes_size();
+            next_offset = next_tag.next_tag_offset();
+            tag.merge(next_tag);
+        }
+
+        Ok(tag)
+    }
+
+    /// Merges a tag found later in the stream into this one. If the later tag is flagged as an
+    /// update, its frames override the frames of this tag the spec doesn't allow next to them,
+    /// as a TIT2 frame or a COMM frame with the same language and description. Otherwise, only
+    /// the later frames allowed next to the frames of this tag are added.
+    pub fn merge(&mut self, later: Tag) {
+        let is_update = later
+            .extended_header
+            .as_ref()
+            .is_some_and(|xheader| xheader.is_update());
+
+        // SEEK and RBUF describe the stream from the tag they're in, so only the last ones count
+        self.frames
+            .retain(|frame| !matches!(frame.frame_id().bytes(), b"SEEK" | b"RBUF"));
+
+        let rules = self.frame_rules();
+        let later_rules = later.frame_rules();
+        let conflicts =
+            |(frame_id, rule): &(FrameID, FrameMultiplicity),
+             (other_id, other_rule): &(FrameID, FrameMultiplicity)| {
+                frame_id == other_id && rule.conflicts_with(other_rule)
+            };
+
+        if is_update {
+            self.frames = std::mem::take(&mut self.frames)
+                .into_iter()
+                .zip(rules)
+                .filter(|(_, rule)| {
+                    !later_rules
+                        .iter()
+                        .any(|later_rule| conflicts(rule, later_rule))
+                })
+                .map(|(frame, _)| frame)
+                .collect();
+            self.frames.extend(later.frames);
+        } else {
+            let new_frames = later
+                .frames
+                .into_iter()
+                .zip(later_rules)
+                .filter(|(_, later_rule)| !rules.iter().any(|rule| conflicts(rule, later_rule)))
+                .map(|(frame, _)| frame)
+                .collect::<Vec<_>>();
+            self.frames.extend(new_frames);
+        }
+    }
+
+    fn frame_rules(&self) -> Vec<(FrameID, FrameMultiplicity)> {
+        self.frames
+            .iter()
+            .map(|frame| (frame.frame_id().clone(), self.multiplicity(frame)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{
+        builder::TagBuilder,
+        frame::Frame,
+        test_utils::{flagged_frame, frame},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn tag_bytes(update: bool, frames: Vec<Frame>) -> Vec<u8> {
+        TagBuilder::new()
+            .update(update)
+            .frames(frames)
+            .build()
+            .unwrap()
+            .to_bytes()
+    }
+
+    #[test]
+    fn follow_seek_to_update() {
+        let first = tag_bytes(
+            false,
+            vec![
+                frame(b"TIT2", b"\x00Old title"),
+                frame(b"TALB", b"\x00Album"),
+                frame(b"SEEK", &[0, 0, 0, 4]),
+            ],
+        );
+        let second = tag_bytes(true, vec![frame(b"TIT2", b"\x00New title")]);
+
+        let mut bytes = first;
+        bytes.extend([0xFF, 0xFB, 0x90, 0x00]);
+        bytes.extend(second);
+        bytes.extend([0xFF, 0xFB, 0x90, 0x00]);
+
+        let tag = Tag::read_following_offsets(&mut Cursor::new(bytes)).unwrap();
+        let frames = tag
+            .frames
+            .iter()
+            .map(|frame| (frame.frame_id().to_string(), frame.data()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            frames,
+            vec![
+                (String::from("TALB"), &b"\x00Album"[..]),
+                (String::from("TIT2"), &b"\x00New title"[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_tag_past_minimum_offset() {
+        let later = tag_bytes(true, vec![frame(b"TIT2", b"\x00New title")]);
+        let read = |offset_frame: Frame| {
+            let mut bytes = tag_bytes(false, vec![offset_frame]);
+            bytes.extend([0xFF, 0xFB, 0x90, 0x00]);
+            bytes.extend([0; 100]);
+            bytes.extend(&later);
+            Tag::read_following_offsets(&mut Cursor::new(bytes)).unwrap()
+        };
+
+        // The next tag is 104 bytes further, past the minimum offset of SEEK
+        let tag = read(frame(b"SEEK", &[0, 0, 0, 4]));
+        assert_eq!(tag.get(b"TIT2").unwrap().data(), b"\x00New title");
+
+        // But RBUF tells where it is exactly
+        let tag = read(frame(b"RBUF", &[0, 0x10, 0, 0, 0, 0, 0, 4]));
+        assert_eq!(tag.get(b"TIT2"), None);
+    }
+
+    #[test]
+    fn offset_without_tag() {
+        let mut bytes = tag_bytes(false, vec![frame(b"SEEK", &[0, 0, 0, 100])]);
+        bytes.extend([0xFF, 0xFB, 0x90, 0x00]);
+
+        let tag = Tag::read_following_offsets(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(tag.offset_to_next_tag(), Some(100));
+
+        // Grouped with 0x90
+        let tag = TagBuilder::new()
+            .frame(flagged_frame(b"SEEK", 0b0100_0000, &[0x90, 0, 0, 0, 100]))
+            .build()
+            .unwrap();
+        assert_eq!(tag.offset_to_next_tag(), Some(100));
+    }
+
+    #[test]
+    fn merge_by_uniqueness_key() {
+        let read = |update, frames| Tag::read(&mut Cursor::new(tag_bytes(update, frames))).unwrap();
+        let earlier = || {
+            read(
+                false,
+                vec![
+                    frame(b"TIT2", b"\x00Old title"),
+                    frame(b"COMM", b"\x00engSource\x00Vinyl"),
+                    frame(b"COMM", b"\x00engNotes\x00Remastered"),
+                ],
+            )
+        };
+        let later_frames = || {
+            vec![
+                frame(b"TIT2", b"\x00New title"),
+                frame(b"COMM", b"\x00engSource\x00CD"),
+                frame(b"COMM", b"\x00engMood\x00Calm"),
+            ]
+        };
+        let data = |tag: &Tag| {
+            tag.frames
+                .iter()
+                .map(|frame| frame.data().to_vec())
+                .collect::<Vec<_>>()
+        };
+
+        let mut tag = earlier();
+        tag.merge(read(true, later_frames()));
+        assert_eq!(
+            data(&tag),
+            [
+                &b"\x00engNotes\x00Remastered"[..],
+                b"\x00New title",
+                b"\x00engSource\x00CD",
+                b"\x00engMood\x00Calm",
+            ]
+        );
+
+        let mut tag = earlier();
+        tag.merge(read(false, later_frames()));
+        assert_eq!(
+            data(&tag),
+            [
+                &b"\x00Old title"[..],
+                b"\x00engSource\x00Vinyl",
+                b"\x00engNotes\x00Remastered",
+                b"\x00engMood\x00Calm",
+            ]
+        );
+    }
+}
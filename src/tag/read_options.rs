@@ -0,0 +1,160 @@
+use std::io::{Read, Seek};
+
+use super::{
+    decryption::FrameDecryptors,
+    frame::{
+        frame_reader::{FrameFilter, FrameReader},
+        Frame,
+    },
+    Tag, TagReadError,
+};
+
+/// What [`Tag::read_with_options`] reads of a tag. By default, everything.
+#[derive(Default)]
+pub struct ReadOptions {
+    /// Only reads the header and extended header, which is enough to know the size and version
+    /// of the tag. The frames and padding are left empty.
+    pub only_header: bool,
+    /// Only keeps the frames it returns true for
+    pub frame_filter: Option<FrameFilter>,
+    /// in bytes, the frames holding more data are skipped
+    pub max_frame_size: Option<u32>,
+    /// Skips the APIC frames, which are usually the biggest ones
+    pub skip_pictures: bool,
+    /// Decrypts the encrypted frames whose method has a registered decryptor, before their
+    /// content is parsed. The ENCR frames are kept even if the filter leaves them out, as they
+    /// tell which method each frame is encrypted with.
+    pub decryptors: Option<FrameDecryptors>,
+}
+
+impl ReadOptions {
+    fn read_tag<R: Read>(mut self, frame_reader: FrameReader<R>) -> Result<Tag, TagReadError> {
+        let decryptors = self.decryptors.take();
+        let mut tag = Tag::read_frames(self.apply(frame_reader, decryptors.is_some()))?;
+        if let Some(decryptors) = decryptors {
+            tag.decrypt_frames(&decryptors)?;
+        }
+        Ok(tag)
+    }
+
+    fn apply<R: Read>(
+        self,
+        mut frame_reader: FrameReader<R>,
+        keep_registrations: bool,
+    ) -> FrameReader<R> {
+        if self.only_header {
+            frame_reader.skip_rest();
+            return frame_reader;
+        }
+        if let Some(max_frame_size) = self.max_frame_size {
+            frame_reader = frame_reader.max_frame_size(max_frame_size);
+        }
+        if self.frame_filter.is_some() || self.skip_pictures {
+            let frame_filter = self.frame_filter;
+            let skip_pictures = self.skip_pictures;
+            frame_reader = frame_reader.only(move |frame_id| {
+                if keep_registrations && frame_id.bytes() == b"ENCR" {
+                    return true;
+                }
+                !(skip_pictures && frame_id.bytes() == b"APIC")
+                    && frame_filter.as_ref().is_none_or(|filter| filter(frame_id))
+            });
+        }
+        frame_reader
+    }
+}
+
+impl Tag {
+    /// Same as [`Tag::read`], but only reads what the options ask for. The data of the frames
+    /// left out is still read, and dropped.
+    pub fn read_with_options(
+        reader: &mut impl Read,
+        options: ReadOptions,
+    ) -> Result<Tag, TagReadError> {
+        options.read_tag(FrameReader::new(reader)?)
+    }
+
+    /// Same as [`Tag::read_with_options`], but seeks past the frames left out.
+    pub fn read_seeking_with_options(
+        reader: &mut (impl Read + Seek),
+        options: ReadOptions,
+    ) -> Result<Tag, TagReadError> {
+        options.read_tag(FrameReader::new(reader)?.seeking())
+    }
+
+    fn read_frames<R: Read>(mut frame_reader: FrameReader<R>) -> Result<Tag, TagReadError> {
+        let mut frames: Vec<Frame> = vec![];
+        for frame in &mut frame_reader {
+            let index = frames.len() as u32 + 1;
+            frames.push(frame.map_err(|error| TagReadError::FrameReadError(index, error))?);
+        }
+
+        Ok(Tag {
+            header: frame_reader.header().clone(),
+            extended_header: frame_reader.extended_header().cloned(),
+            // The footer is a copy of the header placed after the padding, so it is skipped
+            footer: None,
+            frames,
+            padding: frame_reader.padding().unwrap_or(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{builder::TagBuilder, test_utils::frame};
+    use pretty_assertions::assert_eq;
+
+    fn frame_ids(tag: &Tag) -> Vec<&[u8; 4]> {
+        tag.frames()
+            .iter()
+            .map(|frame| frame.frame_id().bytes())
+            .collect()
+    }
+
+    #[test]
+    fn selective_reads() {
+        let tag = TagBuilder::new()
+            .padding(10)
+            .frame(frame(b"TIT2", b"\x00Title"))
+            .frame(frame(b"APIC", &[0; 64]))
+            .frame(frame(b"PRIV", &[1; 32]))
+            .frame(frame(b"TPE1", b"\x00Artist"))
+            .build()
+            .unwrap();
+        let bytes = [tag.to_bytes(), b"audio".to_vec()].concat();
+        let audio_offset = bytes.len() as u64 - 5;
+
+        let mut reader = Cursor::new(&bytes);
+        let options = ReadOptions {
+            skip_pictures: true,
+            max_frame_size: Some(16),
+            ..ReadOptions::default()
+        };
+        let read_tag = Tag::read_seeking_with_options(&mut reader, options).unwrap();
+        assert_eq!(frame_ids(&read_tag), [b"TIT2", b"TPE1"]);
+        assert_eq!(read_tag.padding(), 10);
+        assert_eq!(reader.position(), audio_offset);
+
+        let options = ReadOptions {
+            frame_filter: Some(Box::new(|id| id.bytes() != b"TIT2")),
+            skip_pictures: true,
+            ..ReadOptions::default()
+        };
+        let read_tag = Tag::read_with_options(&mut &bytes[..], options).unwrap();
+        assert_eq!(frame_ids(&read_tag), [b"PRIV", b"TPE1"]);
+
+        let mut reader = Cursor::new(&bytes);
+        let options = ReadOptions {
+            only_header: true,
+            ..ReadOptions::default()
+        };
+        let read_tag = Tag::read_seeking_with_options(&mut reader, options).unwrap();
+        assert!(read_tag.frames().is_empty());
+        assert_eq!(read_tag.header(), tag.header());
+        assert_eq!(reader.position(), audio_offset);
+    }
+}
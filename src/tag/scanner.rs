@@ -0,0 +1,162 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::find_substring;
+
+use super::{header::HeaderReadError, Tag, TagReadError};
+
+/// in bytes, read at once while looking for a tag in a stream
+const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Whether the bytes could be a tag header: "ID3", a major version from 2 to 4, a revision that
+/// isn't 0xFF, none of the flags undefined by that version, and a syncsafe tag size.
+pub fn is_plausible_header(bytes: &[u8]) -> bool {
+    let undefined_flags = match bytes.get(3) {
+        Some(2) => 0b0011_1111,
+        Some(3) => 0b0001_1111,
+        Some(4) => 0b0000_1111,
+        _ => return false,
+    };
+    bytes.len() >= 10
+        && &bytes[0..3] == b"ID3"
+        && bytes[4] != 0xFF
+        && bytes[5] & undefined_flags == 0
+        && bytes[6..10].iter().all(|byte| *byte < 0x80)
+}
+
+/// Offset of the first plausible tag header in the bytes.
+pub fn find_header(bytes: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(position) = find_substring(&bytes[offset..], b"ID3") {
+        let candidate = offset + position;
+        if is_plausible_header(&bytes[candidate..]) {
+            return Some(candidate);
+        }
+        offset = candidate + 1;
+    }
+    None
+}
+
+impl Tag {
+    /// Looks for a tag header from the current position of the reader, as some files have junk
+    /// or a RIFF header before the tag. When one is found, the reader is left at its start and
+    /// its position is returned. Otherwise, the reader is left at the end of the stream.
+    pub fn find(reader: &mut (impl Read + Seek)) -> io::Result<Option<u64>> {
+        Tag::find_within(reader, u64::MAX)
+    }
+
+    /// Same as [`Tag::find`], but gives up after scanning the given number of bytes. The reader
+    /// is then left right after them.
+    pub fn find_within(reader: &mut (impl Read + Seek), limit: u64) -> io::Result<Option<u64>> {
+        let mut window_start = reader.stream_position()?;
+        let mut window = vec![];
+        let mut chunk = vec![0; SCAN_CHUNK_SIZE];
+        let mut remaining = limit;
+        loop {
+            let chunk_size = remaining.min(SCAN_CHUNK_SIZE as u64) as usize;
+            let read = match reader.read(&mut chunk[..chunk_size]) {
+                Ok(0) => return Ok(None),
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            window.extend_from_slice(&chunk[..read]);
+            remaining -= read as u64;
+
+            if let Some(offset) = find_header(&window) {
+                let position = window_start + offset as u64;
+                reader.seek(SeekFrom::Start(position))?;
+                return Ok(Some(position));
+            }
+
+            // Keeps the end of the window, in case a header is split between two chunks
+            let scanned = window.len().saturating_sub(9);
+            window.drain(..scanned);
+            window_start += scanned as u64;
+        }
+    }
+
+    /// Same as [`Tag::read`], but reads the first tag found with [`Tag::find`].
+    pub fn read_anywhere(reader: &mut (impl Read + Seek)) -> Result<Tag, TagReadError> {
+        match Tag::find(reader) {
+            Ok(Some(_)) => Tag::read(reader),
+            Ok(None) => Err(HeaderReadError::ID3NotFound.into()),
+            Err(error) => Err(TagReadError::ScanError(error.kind())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{builder::TagBuilder, test_utils::frame};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn plausible_headers() {
+        assert_eq!(
+            find_header(b"\x00\x00ID3\x04\x00\x00\x00\x00\x01\x7F"),
+            Some(2)
+        );
+        assert_eq!(
+            find_header(b"RIFF\x24\x00\x00\x00WAVEid3 ID3\x03\x00\x00\x00\x00\x00\x00"),
+            Some(16)
+        );
+        // The first candidates have an invalid version, then an invalid size
+        assert_eq!(
+            find_header(
+                b"ID3\xFF\x00\x00\x00\x00\x00\x00\
+                  ID3\x04\x00\x00\x80\x00\x00\x00\
+                  ID3\x04\x00\x00\x00\x00\x00\x00"
+            ),
+            Some(20)
+        );
+        // A version without tags, then an undefined ID3v2.4 flag
+        assert_eq!(
+            find_header(
+                b"ID3\x05\x00\x00\x00\x00\x00\x00\
+                  ID3\x04\x00\x08\x00\x00\x00\x00\
+                  ID3\x03\x00\x20\x00\x00\x00\x00"
+            ),
+            Some(20)
+        );
+        assert_eq!(find_header(b"ID3\x04\x00\x00\x00\x00"), None);
+        assert_eq!(find_header(b""), None);
+    }
+
+    #[test]
+    fn tag_after_junk() {
+        let tag = TagBuilder::new()
+            .frame(frame(b"TIT2", b"\x03Title"))
+            .build()
+            .unwrap();
+        // The header is split between the first two chunks read
+        let junk_size = SCAN_CHUNK_SIZE - 4;
+        let bytes = [vec![0xFF; junk_size], tag.to_bytes(), b"audio".to_vec()].concat();
+
+        let mut reader = Cursor::new(&bytes);
+        assert_eq!(Tag::find(&mut reader).unwrap(), Some(junk_size as u64));
+        assert_eq!(Tag::read_anywhere(&mut reader), Ok(tag.clone()));
+
+        let mut reader = Cursor::new(&bytes);
+        assert_eq!(
+            Tag::find_within(&mut reader, junk_size as u64).unwrap(),
+            None
+        );
+        assert_eq!(reader.position(), junk_size as u64);
+        reader.set_position(0);
+        assert_eq!(
+            Tag::find_within(&mut reader, junk_size as u64 + 10).unwrap(),
+            Some(junk_size as u64)
+        );
+
+        let mut reader = Cursor::new(&bytes[..junk_size + 8]);
+        assert_eq!(Tag::find(&mut reader).unwrap(), None);
+        reader.set_position(0);
+        assert_eq!(
+            Tag::read_anywhere(&mut reader),
+            Err(TagReadError::HeaderError(HeaderReadError::ID3NotFound))
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use thiserror::Error;
+
+use super::{
+    frame::{frame_id::FrameID, signature::Signature, Frame},
+    Tag,
+};
+
+#[derive(PartialEq, Clone, Debug, Error)]
+pub enum SignatureError {
+    #[error("No frame belongs to the group {0:#04X}")]
+    EmptyGroup(u8),
+    #[error("No SIGN frame signs the group {0:#04X}")]
+    MissingSignature(u8),
+    #[error("{0}")]
+    Custom(String),
+}
+
+/// Produces signatures over the canonical bytes of a group of frames.
+pub trait Signer {
+    fn sign(&self, group_symbol: u8, canonical_bytes: &[u8]) -> Result<Vec<u8>, SignatureError>;
+}
+
+/// Checks signatures produced by a [`Signer`].
+pub trait Verifier {
+    fn verify(
+        &self,
+        group_symbol: u8,
+        canonical_bytes: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, SignatureError>;
+}
+
+fn sign_frame_id() -> FrameID {
+    FrameID::try_from(*b"SIGN").unwrap()
+}
+
+impl Tag {
+    fn signatures(&self, group_symbol: u8) -> Vec<Signature> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.frame_id() == &sign_frame_id())
+            .filter_map(|frame| Signature::parse(frame.data()).ok())
+            .filter(|signature| signature.group_symbol == group_symbol)
+            .collect()
+    }
+
+    /// Frames flagged as belonging to the given group, in the order they appear in the tag.
+    /// SIGN frames are never part of a group.
+    pub fn group_frames(&self, group_symbol: u8) -> Vec<&Frame> {
+        let major_version = self.header.major_version();
+        self.frames
+            .iter()
+            .filter(|frame| frame.frame_id() != &sign_frame_id())
+            .filter(|frame| {
+                frame
+                    .flag_data(major_version)
+                    .is_ok_and(|flag_data| flag_data.group_symbol == Some(group_symbol))
+            })
+            .collect()
+    }
+
+    /// The bytes a group signature is computed over: the header and data of every frame in the
+    /// group, concatenated in tag order.
+    pub fn group_canonical_bytes(&self, group_symbol: u8) -> Result<Vec<u8>, SignatureError> {
+        let major_version = self.header.major_version();
+        let frames = self.group_frames(group_symbol);
+        if frames.is_empty() {
+            return Err(SignatureError::EmptyGroup(group_symbol));
+        }
+        Ok(frames
+            .into_iter()
+            .flat_map(|frame| frame.to_bytes(major_version))
+            .collect())
+    }
+
+    /// Signs the group, replacing any SIGN frame previously made for it.
+    pub fn sign_group(
+        &mut self,
+        group_symbol: u8,
+        signer: &impl Signer,
+    ) -> Result<(), SignatureError> {
+        let canonical_bytes = self.group_canonical_bytes(group_symbol)?;
+        let signature = Signature {
+            group_symbol,
+            signature: signer.sign(group_symbol, &canonical_bytes)?,
+        };
+
+        self.frames.retain(|frame| {
+            frame.frame_id() != &sign_frame_id()
+                || Signature::parse(frame.data())
+                    .is_ok_and(|signature| signature.group_symbol != group_symbol)
+        });
+        self.frames
+            .push(Frame::new(sign_frame_id(), signature.to_bytes()));
+        Ok(())
+    }
+
+    /// Checks every SIGN frame made for the group. A tampered frame, or a frame added to or
+    /// removed from the group, makes the verification fail.
+    pub fn verify_group(
+        &self,
+        group_symbol: u8,
+        verifier: &impl Verifier,
+    ) -> Result<bool, SignatureError> {
+        let signatures = self.signatures(group_symbol);
+        if signatures.is_empty() {
+            return Err(SignatureError::MissingSignature(group_symbol));
+        }
+        // A group emptied after signing still has to be checked against its signature
+        let canonical_bytes = self.group_canonical_bytes(group_symbol).unwrap_or_default();
+        for signature in signatures {
+            if !verifier.verify(group_symbol, &canonical_bytes, &signature.signature)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::test_utils::{flagged_frame, frame, read_back};
+    use pretty_assertions::assert_eq;
+
+    /// Not a real signature, but enough to tell whether the bytes changed
+    struct Checksum;
+
+    impl Signer for Checksum {
+        fn sign(&self, _: u8, canonical_bytes: &[u8]) -> Result<Vec<u8>, SignatureError> {
+            let sum = canonical_bytes.iter().fold(0u32, |sum, byte| {
+                sum.wrapping_mul(31).wrapping_add(*byte as u32)
+            });
+            Ok(sum.to_be_bytes().to_vec())
+        }
+    }
+
+    impl Verifier for Checksum {
+        fn verify(
+            &self,
+            group_symbol: u8,
+            canonical_bytes: &[u8],
+            signature: &[u8],
+        ) -> Result<bool, SignatureError> {
+            Ok(self.sign(group_symbol, canonical_bytes)? == signature)
+        }
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let mut tag = read_back(vec![
+            flagged_frame(b"TIT2", 0b0100_0000, b"\x90\x00Title"),
+            flagged_frame(b"TPE1", 0b0100_0000, b"\x90\x00Artist"),
+            frame(b"TALB", b"\x00Not signed"),
+        ]);
+
+        assert_eq!(
+            tag.verify_group(0x90, &Checksum),
+            Err(SignatureError::MissingSignature(0x90))
+        );
+        tag.sign_group(0x90, &Checksum).unwrap();
+        tag.sign_group(0x90, &Checksum).unwrap();
+        assert_eq!(tag.signatures(0x90).len(), 1);
+        assert_eq!(tag.verify_group(0x90, &Checksum), Ok(true));
+
+        tag.frames[1] = frame(b"TPE1", b"\x00Impostor");
+        assert_eq!(tag.verify_group(0x90, &Checksum), Ok(false));
+    }
+}
@@ -0,0 +1,153 @@
+use super::{
+    extended_header::ExtendedHeader,
+    frame::{frame_ref::FrameRef, FrameReadError},
+    header::{Header, HeaderFlagType},
+    Tag, TagReadError,
+};
+
+/// A tag whose frames borrow their data from the bytes it was parsed from, as a memory mapped
+/// file.
+#[derive(PartialEq, Clone, Debug)]
+pub struct TagRef<'a> {
+    header: Header,
+    extended_header: Option<ExtendedHeader>,
+    frames: Vec<FrameRef<'a>>,
+    padding: u32,
+}
+
+impl<'a> TagRef<'a> {
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn extended_header(&self) -> Option<&ExtendedHeader> {
+        self.extended_header.as_ref()
+    }
+
+    /// Every frame, in the order they appear in the tag.
+    pub fn frames(&self) -> &[FrameRef<'a>] {
+        &self.frames
+    }
+
+    /// The first frame with the given id.
+    pub fn get(&self, id: &[u8; 4]) -> Option<&FrameRef<'a>> {
+        self.frames
+            .iter()
+            .find(|frame| frame.frame_id().bytes() == id)
+    }
+
+    pub fn get_all(&self, id: &[u8; 4]) -> impl Iterator<Item = &FrameRef<'a>> {
+        let id = *id;
+        self.frames
+            .iter()
+            .filter(move |frame| frame.frame_id().bytes() == &id)
+    }
+
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// in bytes, from the start of the header to the end of the footer, if present
+    pub fn bytes_size(&self) -> u32 {
+        let footer_size = if self.header.is_flag_set(&HeaderFlagType::FooterPresent) {
+            10
+        } else {
+            0
+        };
+        self.header.bytes_size() + self.header.tag_size() + footer_size
+    }
+
+    /// Copies the frames into an owned tag.
+    pub fn to_tag(&self) -> Tag {
+        Tag {
+            header: self.header.clone(),
+            extended_header: self.extended_header.clone(),
+            frames: self.frames.iter().map(FrameRef::to_frame).collect(),
+            padding: self.padding,
+            footer: None,
+        }
+    }
+}
+
+impl Tag {
+    /// Parses the tag at the start of the bytes without copying the frames. Their content is
+    /// only decoded when asked for.
+    pub fn parse(bytes: &[u8]) -> Result<TagRef<'_>, TagReadError> {
+        let mut rest = bytes;
+        let header = Header::read(&mut rest)?;
+
+        let extended_header = if header.is_flag_set(&HeaderFlagType::ExtendedHeader) {
+            Some(ExtendedHeader::read(&mut rest, header.major_version())?)
+        } else {
+            None
+        };
+
+        // The tag size excludes the header, but includes the extended header
+        let extended_header_size = bytes.len() - header.bytes_size() as usize - rest.len();
+        let frames_size = (header.tag_size() as usize).saturating_sub(extended_header_size);
+        let mut frames_bytes = &rest[..frames_size.min(rest.len())];
+
+        let major_version = header.major_version();
+        let mut frames = vec![];
+        // Too few bytes for a frame header, or a frame id that isn't valid, start the padding
+        while frames_bytes.len() >= 10 {
+            let frame = match FrameRef::parse(&mut frames_bytes, major_version) {
+                Err(FrameReadError::InvalidFrameID(_)) => break,
+                Err(frame_read_error) => {
+                    return Err(TagReadError::FrameReadError(
+                        frames.len() as u32 + 1,
+                        frame_read_error,
+                    ));
+                }
+                Ok(frame) => frame,
+            };
+            frames.push(frame);
+        }
+
+        let frames_length: usize = frames.iter().map(|frame| frame.bytes_size() as usize).sum();
+        let padding = frames_size.saturating_sub(frames_length) as u32;
+
+        Ok(TagRef {
+            header,
+            extended_header,
+            frames,
+            padding,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::tag::{builder::TagBuilder, frame::frame_type::FrameType, test_utils::frame};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn same_as_read() {
+        let tag = TagBuilder::new()
+            .padding(7)
+            .crc(true)
+            .frame(frame(b"TIT2", b"\x03Title"))
+            .frame(frame(b"PRIV", b"owner\x00data"))
+            .build()
+            .unwrap();
+        let bytes = [tag.to_bytes(), b"audio".to_vec()].concat();
+
+        let tag_ref = Tag::parse(&bytes).unwrap();
+        assert_eq!(tag_ref.padding(), 7);
+        assert_eq!(tag_ref.bytes_size(), tag.bytes_size());
+        assert_eq!(
+            tag_ref.to_tag(),
+            Tag::read(&mut Cursor::new(&bytes)).unwrap()
+        );
+
+        let title = tag_ref.get(b"TIT2").unwrap();
+        assert!(matches!(
+            title.frame_type(4),
+            Ok(FrameType::TextInformation(Ok(_)))
+        ));
+        assert_eq!(tag_ref.get_all(b"PRIV").count(), 1);
+    }
+}
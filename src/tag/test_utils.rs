@@ -0,0 +1,36 @@
+use crate::utils::write_syncsafe_integer;
+
+use super::{
+    builder::TagBuilder,
+    frame::{frame_id::FrameID, Frame},
+    Tag,
+};
+
+/// A frame with no flags set.
+pub fn frame(id: &[u8; 4], data: &[u8]) -> Frame {
+    Frame::new(FrameID::try_from(*id).unwrap(), data.to_vec())
+}
+
+/// An ID3v2.4 frame with the given format flags, whose data starts with the flag data.
+pub fn flagged_frame(id: &[u8; 4], format_flags: u8, data: &[u8]) -> Frame {
+    let mut bytes = id.to_vec();
+    bytes.extend_from_slice(&write_syncsafe_integer(data.len() as u32));
+    bytes.extend_from_slice(&[0, format_flags]);
+    bytes.extend_from_slice(data);
+    Frame::read(&mut &bytes[..]).unwrap()
+}
+
+/// An ID3v2.4 tag holding the frames, with some padding, as it is stored in a file.
+pub fn tag_bytes(frames: Vec<Frame>) -> Vec<u8> {
+    TagBuilder::new()
+        .padding(20)
+        .frames(frames)
+        .build()
+        .unwrap()
+        .to_bytes()
+}
+
+/// Writes an ID3v2.4 tag holding the frames, and reads it back.
+pub fn read_back(frames: Vec<Frame>) -> Tag {
+    Tag::read(&mut &tag_bytes(frames)[..]).unwrap()
+}
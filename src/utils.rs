@@ -5,6 +5,15 @@ pub fn read_syncsafe_integer(bytes: [u8; 4]) -> u32 {
         | ((bytes[0] as u32) << 21)
 }
 
+pub fn write_syncsafe_integer(value: u32) -> [u8; 4] {
+    [
+        ((value >> 21) & 0x7F) as u8,
+        ((value >> 14) & 0x7F) as u8,
+        ((value >> 7) & 0x7F) as u8,
+        (value & 0x7F) as u8,
+    ]
+}
+
 pub fn read_syncsafe_integer_5bytes(bytes: [u8; 5]) -> u64 {
     (bytes[4] as u64)
         | ((bytes[3] as u64) << 7)
@@ -13,6 +22,16 @@ pub fn read_syncsafe_integer_5bytes(bytes: [u8; 5]) -> u64 {
         | ((bytes[0] as u64) << 28)
 }
 
+pub fn write_syncsafe_integer_5bytes(value: u64) -> [u8; 5] {
+    [
+        ((value >> 28) & 0x7F) as u8,
+        ((value >> 21) & 0x7F) as u8,
+        ((value >> 14) & 0x7F) as u8,
+        ((value >> 7) & 0x7F) as u8,
+        (value & 0x7F) as u8,
+    ]
+}
+
 #[derive(PartialEq, Clone, Debug, PartialOrd)]
 pub enum BitPosition {
     LSB,
@@ -88,6 +107,76 @@ pub fn latin1_to_string(latin1: &[u8]) -> String {
     latin1.iter().map(|byte| *byte as char).collect()
 }
 
+/// Characters outside of ISO-8859-1 are replaced with a question mark.
 pub fn string_to_latin1(string: &str) -> Vec<u8> {
-    string.chars().map(|char| char as u8).collect()
+    string
+        .chars()
+        .map(|char| u8::try_from(char).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Reads big endian values of arbitrary bit widths from a byte slice, most significant bit first.
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// in bits
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+
+    /// Reads `count` bits, which must be at most 32. Returns `None` if there aren't enough bits left.
+    pub fn read_bits(&mut self, count: u8) -> Option<u32> {
+        assert!(count <= 32, "Cannot read more than 32 bits at once");
+        if self.remaining_bits() < count as usize {
+            return None;
+        }
+
+        let mut value = 0u32;
+        for _ in 0..count {
+            let byte = self.bytes[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | bit as u32;
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+/// Writes big endian values of arbitrary bit widths, most significant bit first. The last byte is
+/// padded with zeroes.
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    /// in bits
+    position: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the `count` least significant bits of `value`. `count` must be at most 32.
+    pub fn write_bits(&mut self, value: u32, count: u8) {
+        assert!(count <= 32, "Cannot write more than 32 bits at once");
+        for shift in (0..count).rev() {
+            if self.position.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> shift) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.position % 8);
+            self.position += 1;
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
 }